@@ -0,0 +1,451 @@
+use crate::names::{identifier, pascal_case, type_name, Names};
+use jtd::{Schema, Type};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Converts a JSON Typedef schema into an Apache Avro schema.
+///
+/// The root schema is named `Root`, and other named types (records and enums)
+/// are named after the path to them. If the root schema is empty but has
+/// `definitions`, then the output is a list of the definitions' schemas, with
+/// names based on the name of each definition.
+///
+/// Objects become records, and nullable schemas and optional properties become
+/// a union with `null`. The values form becomes a `map`, the elements form an
+/// `array`, the enum form an `enum` with sanitized symbols, and the
+/// discriminator form a union of records. Timestamps use the `timestamp-micros`
+/// logical type. Avro has no equivalent of the empty form, so empty schemas are
+/// represented as a nullable (presumably JSON-encoded) `string`.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_avro;
+///
+/// let schema = json!({
+///     "properties": { "name": { "type": "string" }},
+///     "optionalProperties": { "age": { "type": "uint8" }},
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     json!({
+///         "type": "record",
+///         "name": "Root",
+///         "fields": [
+///             { "name": "age", "type": ["null", "int"], "default": null },
+///             { "name": "name", "type": "string" },
+///         ],
+///     }),
+///     to_avro(&schema),
+/// );
+/// ```
+pub fn to_avro(schema: &Schema) -> Value {
+    let mut emitter = Emitter {
+        names: Names::default(),
+        definitions: schema.definitions().clone(),
+        refs: BTreeMap::new(),
+    };
+
+    let is_definitions_only =
+        matches!(schema, Schema::Empty { .. }) && !schema.definitions().is_empty();
+
+    if is_definitions_only {
+        let definitions = schema
+            .definitions()
+            .keys()
+            .map(|name| emitter.convert_ref(name))
+            .collect();
+
+        return Value::Array(definitions);
+    }
+
+    emitter.convert("Root", schema)
+}
+
+struct Emitter {
+    names: Names,
+    definitions: BTreeMap<String, Schema>,
+
+    /// What to use for a ref to a definition that has already been converted.
+    /// Avro requires named types be declared only once, and then referred to
+    /// by name.
+    refs: BTreeMap<String, Value>,
+}
+
+impl Emitter {
+    fn convert(&mut self, name: &str, schema: &Schema) -> Value {
+        let out = match schema {
+            Schema::Empty { .. } => json!("string"),
+            Schema::Ref { ref_, .. } => self.convert_ref(ref_),
+            Schema::Type { type_, .. } => match type_ {
+                Type::Boolean => json!("boolean"),
+                Type::String => json!("string"),
+                Type::Timestamp => json!({ "type": "long", "logicalType": "timestamp-micros" }),
+                Type::Int8 | Type::Uint8 | Type::Int16 | Type::Uint16 | Type::Int32 => {
+                    json!("int")
+                }
+                Type::Uint32 => json!("long"),
+                Type::Float32 => json!("float"),
+                Type::Float64 => json!("double"),
+            },
+            Schema::Enum { enum_, .. } => {
+                let mut symbols = Names::default();
+                json!({
+                    "type": "enum",
+                    "name": self.names.unique(name),
+                    "symbols": enum_
+                        .iter()
+                        .map(|value| symbols.unique(&identifier(value)))
+                        .collect::<Vec<_>>(),
+                })
+            }
+            Schema::Elements { elements, .. } => json!({
+                "type": "array",
+                "items": self.convert(&format!("{}Element", name), elements),
+            }),
+            Schema::Values { values, .. } => json!({
+                "type": "map",
+                "values": self.convert(&format!("{}Value", name), values),
+            }),
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => self.record(name, None, properties, optional_properties),
+            Schema::Discriminator {
+                discriminator,
+                mapping,
+                ..
+            } => {
+                let mut variants = Vec::new();
+                for (tag, sub_schema) in mapping {
+                    if let Schema::Properties {
+                        properties,
+                        optional_properties,
+                        ..
+                    } = sub_schema
+                    {
+                        variants.push(self.record(
+                            &format!("{}{}", name, pascal_case(tag)),
+                            Some(discriminator),
+                            properties,
+                            optional_properties,
+                        ));
+                    }
+                }
+
+                // A nullable discriminator is just one more branch of the
+                // union it becomes.
+                if schema.nullable() {
+                    variants.insert(0, json!("null"));
+                }
+
+                return Value::Array(variants);
+            }
+        };
+
+        match out {
+            // A ref to a discriminator is already a union, which can't be
+            // nested in another one.
+            Value::Array(mut types) if schema.nullable() => {
+                if !types.contains(&json!("null")) {
+                    types.insert(0, json!("null"));
+                }
+
+                Value::Array(types)
+            }
+            out if schema.nullable() => json!(["null", out]),
+            out => out,
+        }
+    }
+
+    fn convert_ref(&mut self, ref_: &str) -> Value {
+        if let Some(converted) = self.refs.get(ref_) {
+            return converted.clone();
+        }
+
+        let definition = self.definitions[ref_].clone();
+        let converted = self.convert(&type_name(ref_), &definition);
+
+        self.refs.insert(ref_.to_owned(), reference(&converted));
+        converted
+    }
+
+    fn record(
+        &mut self,
+        name: &str,
+        discriminator: Option<&str>,
+        properties: &BTreeMap<String, Schema>,
+        optional_properties: &BTreeMap<String, Schema>,
+    ) -> Value {
+        let name = self.names.unique(name);
+
+        let mut field_names = Names::default();
+        let mut fields = Vec::new();
+
+        if let Some(discriminator) = discriminator {
+            fields.push(json!({
+                "name": field_names.unique(&identifier(discriminator)),
+                "type": "string",
+            }));
+        }
+
+        let mut sorted: Vec<_> = properties
+            .iter()
+            .map(|field| (field, false))
+            .chain(optional_properties.iter().map(|field| (field, true)))
+            .collect();
+
+        sorted.sort_by_key(|((key, _), _)| key.as_str());
+
+        for ((key, sub_schema), optional) in sorted {
+            let mut type_ = self.convert(&format!("{}{}", name, pascal_case(key)), sub_schema);
+            let mut field = json!({ "name": field_names.unique(&identifier(key)) });
+
+            if optional {
+                // Optional fields default to null, which must be the first
+                // type in the union. Avro doesn't allow a union directly
+                // inside of another, so a union gets "null" added to it.
+                if !is_nullable_union(&type_) {
+                    match &mut type_ {
+                        Value::Array(types) => types.insert(0, json!("null")),
+                        _ => type_ = json!(["null", type_]),
+                    }
+                }
+
+                field["type"] = type_;
+                field["default"] = Value::Null;
+            } else {
+                field["type"] = type_;
+            }
+
+            fields.push(field);
+        }
+
+        json!({
+            "type": "record",
+            "name": name,
+            "fields": fields,
+        })
+    }
+}
+
+/// Returns a way to refer to `type_` after it has been declared, which is
+/// `type_` with each of the named types declared in it replaced by its name.
+fn reference(type_: &Value) -> Value {
+    match type_ {
+        Value::Array(types) => Value::Array(types.iter().map(reference).collect()),
+        Value::Object(object) => match object["type"].as_str() {
+            Some("record") | Some("enum") => object["name"].clone(),
+            Some("array") => json!({ "type": "array", "items": reference(&object["items"]) }),
+            Some("map") => json!({ "type": "map", "values": reference(&object["values"]) }),
+            _ => type_.clone(),
+        },
+        _ => type_.clone(),
+    }
+}
+
+fn is_nullable_union(type_: &Value) -> bool {
+    type_
+        .as_array()
+        .and_then(|types| types.first())
+        .map(|first| first == "null")
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+
+    fn convert_jtd(schema: Value) -> Value {
+        to_avro(&jtd_schema(schema))
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(json!(["null", "string"]), convert_jtd(json!({})));
+        assert_eq!(
+            json!(["null", { "type": "long", "logicalType": "timestamp-micros" }]),
+            convert_jtd(json!({ "type": "timestamp", "nullable": true }))
+        );
+        assert_eq!(json!("long"), convert_jtd(json!({ "type": "uint32" })));
+        assert_eq!(
+            json!({ "type": "map", "values": { "type": "array", "items": "float" }}),
+            convert_jtd(json!({ "values": { "elements": { "type": "float32" }}}))
+        );
+        assert_eq!(
+            json!({ "type": "enum", "name": "Root", "symbols": ["_1st", "in_progress"] }),
+            convert_jtd(json!({ "enum": ["1st", "in-progress"] }))
+        );
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            json!({
+                "type": "record",
+                "name": "Root",
+                "fields": [
+                    {
+                        "name": "address",
+                        "type": ["null", {
+                            "type": "record",
+                            "name": "RootAddress",
+                            "fields": [{ "name": "zip_code", "type": "string" }],
+                        }],
+                        "default": null,
+                    },
+                    { "name": "nickname", "type": ["null", "string"] },
+                ],
+            }),
+            convert_jtd(json!({
+                "properties": { "nickname": { "type": "string", "nullable": true }},
+                "optionalProperties": {
+                    "address": {
+                        "properties": { "zip-code": { "type": "string" }},
+                        "nullable": true,
+                    },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            json!([
+                "null",
+                {
+                    "type": "record",
+                    "name": "RootBar",
+                    "fields": [{ "name": "kind", "type": "string" }],
+                },
+                {
+                    "type": "record",
+                    "name": "RootFoo",
+                    "fields": [
+                        { "name": "kind", "type": "string" },
+                        { "name": "x", "type": "boolean" },
+                    ],
+                },
+            ]),
+            convert_jtd(json!({
+                "discriminator": "kind",
+                "mapping": {
+                    "foo": { "properties": { "x": { "type": "boolean" }}},
+                    "bar": { "properties": {}},
+                },
+                "nullable": true,
+            }))
+        );
+    }
+
+    #[test]
+    fn optional_discriminator() {
+        assert_eq!(
+            json!({
+                "type": "record",
+                "name": "Root",
+                "fields": [
+                    {
+                        "name": "e",
+                        "type": [
+                            "null",
+                            {
+                                "type": "record",
+                                "name": "RootEA",
+                                "fields": [{ "name": "type", "type": "string" }],
+                            },
+                            {
+                                "type": "record",
+                                "name": "RootEB",
+                                "fields": [{ "name": "type", "type": "string" }],
+                            },
+                        ],
+                        "default": null,
+                    },
+                ],
+            }),
+            convert_jtd(json!({
+                "optionalProperties": {
+                    "e": {
+                        "discriminator": "type",
+                        "mapping": {
+                            "a": { "properties": {}},
+                            "b": { "properties": {}},
+                        },
+                    },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn repeated_refs() {
+        assert_eq!(
+            json!({
+                "type": "record",
+                "name": "Root",
+                "fields": [
+                    {
+                        "name": "a",
+                        "type": ["null", {
+                            "type": "record",
+                            "name": "User",
+                            "fields": [{ "name": "id", "type": "int" }],
+                        }],
+                    },
+                    { "name": "b", "type": ["null", "User"] },
+                    {
+                        "name": "c",
+                        "type": {
+                            "type": "array",
+                            "items": {
+                                "type": "enum",
+                                "name": "StatusesElement",
+                                "symbols": ["done"],
+                            },
+                        },
+                    },
+                    { "name": "d", "type": { "type": "array", "items": "StatusesElement" }},
+                ],
+            }),
+            convert_jtd(json!({
+                "definitions": {
+                    "user": {
+                        "properties": { "id": { "type": "uint8" }},
+                        "nullable": true,
+                    },
+                    "statuses": { "elements": { "enum": ["done"] }},
+                },
+                "properties": {
+                    "a": { "ref": "user" },
+                    "b": { "ref": "user" },
+                    "c": { "ref": "statuses" },
+                    "d": { "ref": "statuses" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions() {
+        assert_eq!(
+            json!([
+                {
+                    "type": "record",
+                    "name": "GetUsersResponse",
+                    "fields": [{ "name": "id", "type": "int" }],
+                },
+            ]),
+            convert_jtd(json!({
+                "definitions": {
+                    "get_users_response": { "properties": { "id": { "type": "uint8" }}},
+                },
+            }))
+        );
+    }
+}
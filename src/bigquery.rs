@@ -0,0 +1,436 @@
+use crate::names::{identifier, Names};
+use jtd::{Schema, Type};
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Converts a JSON Typedef schema into a BigQuery JSON table schema.
+///
+/// The output is the list of fields that BigQuery expects in a schema file.
+/// Each property of the root schema becomes a column. If the root schema isn't
+/// a properties form, then the output has a single column named `value`. If
+/// the root schema is empty but has `definitions`, then the output is an
+/// object with a table schema for each definition.
+///
+/// Required properties get mode `REQUIRED`, optional or nullable properties
+/// `NULLABLE`, and arrays `REPEATED`. Nested objects become a `RECORD`,
+/// timestamps a `TIMESTAMP`, integers an `INT64`, and floats a `FLOAT64`.
+/// Values, discriminator and empty schemas, as well as arrays of arrays, which
+/// BigQuery doesn't support, become `JSON`. So does a recursive ref, such as a
+/// property of a definition that refers back to that definition.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_bigquery;
+///
+/// let schema = json!({
+///     "properties": {
+///         "at": { "type": "timestamp" },
+///         "tags": { "elements": { "type": "string" }},
+///     },
+///     "optionalProperties": { "age": { "type": "uint8" }},
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     json!([
+///         { "name": "age", "type": "INT64", "mode": "NULLABLE" },
+///         { "name": "at", "type": "TIMESTAMP", "mode": "REQUIRED" },
+///         { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+///     ]),
+///     to_bigquery(&schema),
+/// );
+/// ```
+pub fn to_bigquery(schema: &Schema) -> Value {
+    to_bigquery_with_warnings(schema).0
+}
+
+/// Converts a JSON Typedef schema into a BigQuery JSON table schema, like
+/// [`to_bigquery`], and also returns warnings about parts of the schema that
+/// BigQuery can't represent exactly.
+///
+/// BigQuery doesn't allow null elements in an array, so nullable elements are
+/// output as an ordinary `REPEATED` column, and each of them is warned about.
+/// Each warning starts with the path to the column, with a dot between the
+/// names of nested columns, and the name of the table if the output has
+/// several of them.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_bigquery_with_warnings;
+///
+/// let schema = json!({
+///     "properties": {
+///         "tags": { "elements": { "type": "string", "nullable": true }},
+///     },
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     (
+///         json!([{ "name": "tags", "type": "STRING", "mode": "REPEATED" }]),
+///         vec!["tags: elements may be null, but a REPEATED column can't hold nulls".to_owned()],
+///     ),
+///     to_bigquery_with_warnings(&schema),
+/// );
+/// ```
+pub fn to_bigquery_with_warnings(schema: &Schema) -> (Value, Vec<String>) {
+    let converter = Converter {
+        definitions: schema.definitions(),
+        expanding: RefCell::new(Vec::new()),
+        warnings: RefCell::new(Vec::new()),
+    };
+
+    let is_definitions_only =
+        matches!(schema, Schema::Empty { .. }) && !schema.definitions().is_empty();
+
+    let table = if is_definitions_only {
+        schema
+            .definitions()
+            .iter()
+            .map(|(name, sub_schema)| {
+                converter.expanding.borrow_mut().push(name);
+                let table = converter.table(&format!("{}: ", name), sub_schema);
+                converter.expanding.borrow_mut().clear();
+
+                (name.clone(), table)
+            })
+            .collect::<Map<_, _>>()
+            .into()
+    } else {
+        converter.table("", schema)
+    };
+
+    (table, converter.warnings.into_inner())
+}
+
+struct Converter<'a> {
+    definitions: &'a BTreeMap<String, Schema>,
+
+    /// The definitions that the column being converted is inside of. A ref to
+    /// one of them is recursive, and can't be expanded into columns.
+    expanding: RefCell<Vec<&'a str>>,
+
+    warnings: RefCell<Vec<String>>,
+}
+
+impl<'a> Converter<'a> {
+    /// Follows the refs in `schema` to the schema they refer to, adding each
+    /// definition along the way to `expanding`. Returns `None`, and adds
+    /// nothing, if one of the refs is recursive.
+    fn expand(&self, mut schema: &'a Schema) -> Option<&'a Schema> {
+        let mut expanding = self.expanding.borrow_mut();
+        let depth = expanding.len();
+
+        while let Schema::Ref { ref_, .. } = schema {
+            if expanding.contains(&ref_.as_str()) {
+                expanding.truncate(depth);
+                return None;
+            }
+
+            expanding.push(ref_);
+            schema = &self.definitions[ref_];
+        }
+
+        Some(schema)
+    }
+
+    /// Converts `schema` into a list of columns. `prefix` comes before the
+    /// path to each column in warnings.
+    fn table(&self, prefix: &str, schema: &'a Schema) -> Value {
+        let depth = self.expanding.borrow().len();
+
+        // A recursive ref is left as is, and so becomes a JSON column.
+        let table = match self.expand(schema).unwrap_or(schema) {
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => Value::Array(self.fields(prefix, properties, optional_properties)),
+            root => Value::Array(vec![self.field(
+                &format!("{}value", prefix),
+                "value",
+                root,
+                !root.nullable(),
+            )]),
+        };
+
+        self.expanding.borrow_mut().truncate(depth);
+        table
+    }
+
+    fn fields(
+        &self,
+        prefix: &str,
+        properties: &'a BTreeMap<String, Schema>,
+        optional_properties: &'a BTreeMap<String, Schema>,
+    ) -> Vec<Value> {
+        let mut sorted: Vec<_> = properties
+            .iter()
+            .map(|field| (field, true))
+            .chain(optional_properties.iter().map(|field| (field, false)))
+            .collect();
+
+        sorted.sort_by_key(|((key, _), _)| key.as_str());
+
+        // BigQuery column names are case-insensitive, so names are made unique
+        // ignoring case, but otherwise keep the case of the property.
+        let mut names = Names::default();
+        sorted
+            .into_iter()
+            .map(|((key, sub_schema), required)| {
+                let name = identifier(key);
+                let unique = names.unique(&name.to_ascii_lowercase());
+                let name = format!("{}{}", name, &unique[name.len()..]);
+
+                self.field(
+                    &format!("{}{}", prefix, name),
+                    &name,
+                    sub_schema,
+                    required && !sub_schema.nullable(),
+                )
+            })
+            .collect()
+    }
+
+    /// Converts `schema` into a column called `name`. `path` is the path to the
+    /// column for warnings.
+    fn field(&self, path: &str, name: &str, schema: &'a Schema, required: bool) -> Value {
+        let depth = self.expanding.borrow().len();
+
+        // A recursive ref is left as is, and so becomes a JSON column.
+        let schema = self.expand(schema).unwrap_or(schema);
+
+        let (type_, fields, mode) = match schema {
+            Schema::Elements { elements, .. } => {
+                let elements = self.expand(elements).unwrap_or(elements);
+                if elements.nullable() {
+                    self.warnings.borrow_mut().push(format!(
+                        "{}: elements may be null, but a REPEATED column can't hold nulls",
+                        path
+                    ));
+                }
+
+                match elements {
+                    Schema::Elements { .. } => ("JSON", None, "REPEATED"),
+                    _ => {
+                        let (type_, fields) = self.column_type(path, elements);
+                        (type_, fields, "REPEATED")
+                    }
+                }
+            }
+            _ => {
+                let (type_, fields) = self.column_type(path, schema);
+                (
+                    type_,
+                    fields,
+                    if required { "REQUIRED" } else { "NULLABLE" },
+                )
+            }
+        };
+
+        let mut field = json!({ "name": name, "type": type_, "mode": mode });
+        if let Some(fields) = fields {
+            field["fields"] = Value::Array(fields);
+        }
+
+        if let Some(description) = schema.metadata().get("description") {
+            field["description"] = description.clone();
+        }
+
+        self.expanding.borrow_mut().truncate(depth);
+        field
+    }
+
+    fn column_type(&self, path: &str, schema: &'a Schema) -> (&'static str, Option<Vec<Value>>) {
+        match schema {
+            Schema::Type { type_, .. } => (
+                match type_ {
+                    Type::Boolean => "BOOL",
+                    Type::String => "STRING",
+                    Type::Timestamp => "TIMESTAMP",
+                    Type::Float32 | Type::Float64 => "FLOAT64",
+                    _ => "INT64",
+                },
+                None,
+            ),
+            Schema::Enum { .. } => ("STRING", None),
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => (
+                "RECORD",
+                Some(self.fields(&format!("{}.", path), properties, optional_properties)),
+            ),
+            _ => ("JSON", None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+
+    fn convert_jtd(schema: Value) -> Value {
+        to_bigquery(&jtd_schema(schema))
+    }
+
+    #[test]
+    fn non_properties_root() {
+        assert_eq!(
+            json!([{ "name": "value", "type": "JSON", "mode": "NULLABLE" }]),
+            convert_jtd(json!({}))
+        );
+        assert_eq!(
+            json!([{ "name": "value", "type": "FLOAT64", "mode": "REPEATED" }]),
+            convert_jtd(json!({ "elements": { "type": "float32" }}))
+        );
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            json!([
+                {
+                    "name": "address",
+                    "type": "RECORD",
+                    "mode": "NULLABLE",
+                    "fields": [
+                        { "name": "zip_code", "type": "STRING", "mode": "REQUIRED" },
+                    ],
+                },
+                { "name": "attrs", "type": "JSON", "mode": "REQUIRED" },
+                { "name": "matrix", "type": "JSON", "mode": "REPEATED" },
+                { "name": "status", "type": "STRING", "mode": "NULLABLE" },
+            ]),
+            convert_jtd(json!({
+                "properties": {
+                    "address": {
+                        "properties": { "zip-code": { "type": "string" }},
+                        "nullable": true,
+                    },
+                    "attrs": { "values": { "type": "string" }},
+                    "matrix": { "elements": { "elements": { "type": "uint8" }}},
+                },
+                "optionalProperties": {
+                    "status": { "enum": ["a", "b"] },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions() {
+        assert_eq!(
+            json!({
+                "get_users_response": [
+                    { "name": "user", "type": "STRING", "mode": "REQUIRED" },
+                ],
+                "user": [
+                    { "name": "value", "type": "STRING", "mode": "REQUIRED" },
+                ],
+            }),
+            convert_jtd(json!({
+                "definitions": {
+                    "get_users_response": { "properties": { "user": { "ref": "user" }}},
+                    "user": { "type": "string" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn case_insensitive_names() {
+        assert_eq!(
+            json!([
+                { "name": "ID", "type": "STRING", "mode": "REQUIRED" },
+                { "name": "Id2", "type": "STRING", "mode": "REQUIRED" },
+                { "name": "id3", "type": "INT64", "mode": "REQUIRED" },
+            ]),
+            convert_jtd(json!({
+                "properties": {
+                    "ID": { "type": "string" },
+                    "Id": { "type": "string" },
+                    "id": { "type": "uint8" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn recursive_refs() {
+        assert_eq!(
+            json!({ "a": [{ "name": "value", "type": "JSON", "mode": "REQUIRED" }] }),
+            convert_jtd(json!({ "definitions": { "a": { "ref": "a" }}}))
+        );
+
+        assert_eq!(
+            json!([
+                {
+                    "name": "head",
+                    "type": "RECORD",
+                    "mode": "REQUIRED",
+                    "fields": [
+                        { "name": "next", "type": "JSON", "mode": "NULLABLE" },
+                        { "name": "value", "type": "INT64", "mode": "REQUIRED" },
+                    ],
+                },
+                {
+                    "name": "tail",
+                    "type": "RECORD",
+                    "mode": "REQUIRED",
+                    "fields": [
+                        { "name": "next", "type": "JSON", "mode": "NULLABLE" },
+                        { "name": "value", "type": "INT64", "mode": "REQUIRED" },
+                    ],
+                },
+            ]),
+            convert_jtd(json!({
+                "definitions": {
+                    "node": {
+                        "properties": {
+                            "value": { "type": "uint8" },
+                            "next": { "ref": "node", "nullable": true },
+                        },
+                    },
+                },
+                "properties": {
+                    "head": { "ref": "node" },
+                    "tail": { "ref": "node" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn nullable_elements() {
+        let schema = jtd_schema(json!({
+            "definitions": {
+                "event": {
+                    "properties": {
+                        "user": {
+                            "properties": {
+                                "scores": { "elements": { "type": "uint8", "nullable": true }},
+                            },
+                        },
+                    },
+                },
+                "tags": { "elements": { "type": "string", "nullable": true }},
+            },
+        }));
+
+        assert_eq!(
+            vec![
+                "event: user.scores: elements may be null, but a REPEATED column can't hold nulls",
+                "tags: value: elements may be null, but a REPEATED column can't hold nulls",
+            ],
+            to_bigquery_with_warnings(&schema).1
+        );
+    }
+}
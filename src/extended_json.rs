@@ -0,0 +1,148 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+use serde_json::{Number, Value};
+
+/// The result of unwrapping a MongoDB Extended JSON value.
+pub enum Unwrapped {
+    /// The value was not a wrapper, or it was one whose logical value can be
+    /// represented as ordinary JSON.
+    Value(Value),
+
+    /// The value was a wrapper for a number that JSON numbers can't represent
+    /// exactly, and so it is encoded as a string, which is kept here.
+    EncodedNumber(EncodedNumber, String),
+}
+
+/// A kind of number that Extended JSON encodes as a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EncodedNumber {
+    Int64,
+    Decimal128,
+}
+
+impl EncodedNumber {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Int64 => "int64",
+            Self::Decimal128 => "decimal128",
+        }
+    }
+}
+
+/// Unwraps the MongoDB Extended JSON wrapper objects, such as `{"$oid":
+/// "..."}`, into the logical value they represent.
+///
+/// Only the outermost value is unwrapped. Wrappers nested inside of ordinary
+/// objects or arrays are left in place, and get unwrapped as inference
+/// recurses into them.
+pub fn unwrap(value: Value) -> Unwrapped {
+    let obj = match value {
+        Value::Object(obj) if obj.len() == 1 => obj,
+        _ => return Unwrapped::Value(value),
+    };
+
+    let (key, inner) = obj.iter().next().unwrap();
+    let unwrapped = match (key.as_str(), inner) {
+        ("$oid", Value::String(s)) | ("$symbol", Value::String(s)) => {
+            Some(Unwrapped::Value(Value::String(s.clone())))
+        }
+        ("$numberLong", Value::String(s)) => {
+            Some(Unwrapped::EncodedNumber(EncodedNumber::Int64, s.clone()))
+        }
+        ("$numberDecimal", Value::String(s)) => Some(Unwrapped::EncodedNumber(
+            EncodedNumber::Decimal128,
+            s.clone(),
+        )),
+        ("$numberInt", Value::String(s)) => s
+            .parse::<i64>()
+            .ok()
+            .map(|n| Unwrapped::Value(Value::Number(n.into()))),
+        ("$numberDouble", Value::String(s)) => s
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(|n| Unwrapped::Value(Value::Number(n))),
+        ("$date", Value::String(s)) => Some(Unwrapped::Value(Value::String(s.clone()))),
+        ("$date", Value::Number(n)) => n.as_i64().and_then(millis_to_rfc3339),
+        ("$date", Value::Object(date)) => date
+            .get("$numberLong")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse().ok())
+            .and_then(millis_to_rfc3339),
+        _ => None,
+    };
+
+    match unwrapped {
+        Some(unwrapped) => unwrapped,
+        None => Unwrapped::Value(Value::Object(obj)),
+    }
+}
+
+fn millis_to_rfc3339(millis: i64) -> Option<Unwrapped> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|date| date.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .map(|date| Unwrapped::Value(Value::String(date)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn unwrap_value(value: Value) -> Option<Value> {
+        match unwrap(value) {
+            Unwrapped::Value(value) => Some(value),
+            Unwrapped::EncodedNumber(..) => None,
+        }
+    }
+
+    #[test]
+    fn unwrap_dates() {
+        assert_eq!(
+            Some(json!("2020-01-01T00:00:00Z")),
+            unwrap_value(json!({ "$date": "2020-01-01T00:00:00Z" }))
+        );
+
+        assert_eq!(
+            Some(json!("2020-01-01T00:00:00.000Z")),
+            unwrap_value(json!({ "$date": { "$numberLong": "1577836800000" } }))
+        );
+
+        assert_eq!(
+            Some(json!("2020-01-01T00:00:00.000Z")),
+            unwrap_value(json!({ "$date": 1577836800000i64 }))
+        );
+    }
+
+    #[test]
+    fn unwrap_numbers() {
+        assert_eq!(Some(json!(5)), unwrap_value(json!({ "$numberInt": "5" })));
+        assert_eq!(
+            Some(json!(1.5)),
+            unwrap_value(json!({ "$numberDouble": "1.5" }))
+        );
+
+        assert!(matches!(
+            unwrap(json!({ "$numberLong": "5" })),
+            Unwrapped::EncodedNumber(EncodedNumber::Int64, s) if s == "5"
+        ));
+
+        assert!(matches!(
+            unwrap(json!({ "$numberDecimal": "5.5" })),
+            Unwrapped::EncodedNumber(EncodedNumber::Decimal128, s) if s == "5.5"
+        ));
+    }
+
+    #[test]
+    fn unwrap_non_wrappers() {
+        assert_eq!(Some(json!({ "a": 1 })), unwrap_value(json!({ "a": 1 })));
+        assert_eq!(
+            Some(json!({ "$oid": "x", "a": 1 })),
+            unwrap_value(json!({ "$oid": "x", "a": 1 }))
+        );
+        assert_eq!(
+            Some(json!({ "$oid": 1 })),
+            unwrap_value(json!({ "$oid": 1 }))
+        );
+    }
+}
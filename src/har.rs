@@ -0,0 +1,312 @@
+use crate::names::Names;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+/// A JSON body extracted from an entry in a HAR (HTTP Archive) file.
+///
+/// See [`har_examples`] for how these are produced.
+#[derive(Debug, PartialEq)]
+pub struct HarExample {
+    /// The name of the schema this body is an example of.
+    ///
+    /// Names are derived from the entry's method, its normalized URL path
+    /// template, and whether the body is from the request or the response. For
+    /// example, the response to `GET https://example.com/users/42?x=y` is
+    /// named `get_users_id_response`. If different endpoints would get the
+    /// same name, then all but the first of them get a numeric suffix, as in
+    /// `get_users_list2_response`.
+    pub name: String,
+
+    /// The parsed JSON body.
+    pub value: Value,
+}
+
+/// An error returned from [`har_examples`] when the input isn't a HAR file.
+#[derive(Debug)]
+pub struct HarError;
+
+impl fmt::Display for HarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "input is not a HAR file: missing log.entries")
+    }
+}
+
+impl Error for HarError {}
+
+/// Extracts JSON request and response bodies from a HAR file.
+///
+/// Entries are grouped into endpoints by their method and URL path. Path
+/// segments that look like identifiers (numbers, UUIDs, long hex strings or
+/// tokens) are replaced with an `{id}` placeholder, so that `/users/1` and
+/// `/users/2` are treated as the same endpoint.
+///
+/// Only bodies whose MIME type mentions `json` are extracted. Bodies that
+/// aren't valid JSON, or that the HAR stores base64-encoded, are skipped.
+///
+/// ```
+/// use serde_json::json;
+/// use jtd_infer::har_examples;
+///
+/// let har = json!({
+///     "log": {
+///         "entries": [{
+///             "request": { "method": "GET", "url": "https://example.com/users/42" },
+///             "response": {
+///                 "content": { "mimeType": "application/json", "text": "{\"id\":42}" }
+///             }
+///         }]
+///     }
+/// });
+///
+/// let examples = har_examples(&har).unwrap();
+/// assert_eq!(1, examples.len());
+/// assert_eq!("get_users_id_response", examples[0].name);
+/// assert_eq!(json!({ "id": 42 }), examples[0].value);
+/// ```
+pub fn har_examples(har: &Value) -> Result<Vec<HarExample>, HarError> {
+    let entries = har
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or(HarError)?;
+
+    // Different endpoints can have the same name, as with `/users-list` and
+    // `/users_list`, so each endpoint gets a name of its own the first time
+    // it's seen.
+    let mut names = Names::default();
+    let mut endpoints = BTreeMap::new();
+
+    let mut examples = Vec::new();
+    for entry in entries {
+        let method = entry
+            .pointer("/request/method")
+            .and_then(Value::as_str)
+            .unwrap_or("GET");
+
+        let url = match entry.pointer("/request/url").and_then(Value::as_str) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        let path_template = path_template(url);
+        let endpoint = endpoints
+            .entry((method.to_uppercase(), path_template))
+            .or_insert_with_key(|(method, path_template)| {
+                names.unique(&endpoint_name(method, path_template))
+            });
+
+        if let Some(value) = entry.pointer("/request/postData").and_then(parse_body) {
+            examples.push(HarExample {
+                name: format!("{}_request", endpoint),
+                value,
+            });
+        }
+
+        if let Some(value) = entry.pointer("/response/content").and_then(parse_body) {
+            examples.push(HarExample {
+                name: format!("{}_response", endpoint),
+                value,
+            });
+        }
+    }
+
+    Ok(examples)
+}
+
+fn parse_body(content: &Value) -> Option<Value> {
+    let mime_type = content.get("mimeType").and_then(Value::as_str)?;
+    if !mime_type.contains("json") {
+        return None;
+    }
+
+    if content.get("encoding").and_then(Value::as_str) == Some("base64") {
+        return None;
+    }
+
+    let text = content.get("text").and_then(Value::as_str)?;
+    serde_json::from_str(text).ok()
+}
+
+fn path_template(url: &str) -> String {
+    // Strip the scheme and host, if present, and then the query and fragment.
+    let path = match url.find("://") {
+        Some(i) => {
+            let rest = &url[i + 3..];
+            rest.find('/').map(|j| &rest[j..]).unwrap_or("/")
+        }
+        None => url,
+    };
+
+    let path = path.split(['?', '#']).next().unwrap_or("");
+
+    let segments: Vec<_> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| if is_id(segment) { "{id}" } else { segment })
+        .collect();
+
+    format!("/{}", segments.join("/"))
+}
+
+fn is_id(segment: &str) -> bool {
+    let has_digit = segment.chars().any(|c| c.is_ascii_digit());
+    let is_hex = segment.chars().all(|c| c.is_ascii_hexdigit());
+    let is_token = segment
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    segment.chars().all(|c| c.is_ascii_digit())
+        || is_uuid(segment)
+        || (is_hex && has_digit && segment.len() >= 12)
+        || (is_token && has_digit && segment.len() >= 20)
+}
+
+fn is_uuid(segment: &str) -> bool {
+    let groups: Vec<_> = segment.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip(&[8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == *len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn endpoint_name(method: &str, path_template: &str) -> String {
+    let mut parts = vec![method.to_lowercase()];
+    for segment in path_template.split('/').filter(|s| !s.is_empty()) {
+        let part: String = segment
+            .chars()
+            .filter(|c| *c != '{' && *c != '}')
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        parts.push(part.to_lowercase());
+    }
+
+    if parts.len() == 1 {
+        parts.push("root".to_owned());
+    }
+
+    parts.join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn path_template() {
+        assert_eq!("/", super::path_template("https://example.com"));
+        assert_eq!("/", super::path_template("https://example.com/?a=b"));
+        assert_eq!(
+            "/users/{id}/posts",
+            super::path_template("https://example.com/users/42/posts?page=2#top")
+        );
+        assert_eq!(
+            "/orders/{id}",
+            super::path_template("/orders/123e4567-e89b-12d3-a456-426614174000")
+        );
+        assert_eq!(
+            "/docs/{id}",
+            super::path_template("/docs/507f1f77bcf86cd799439011")
+        );
+        assert_eq!("/v2/accounts", super::path_template("/v2/accounts"));
+    }
+
+    #[test]
+    fn endpoint_name() {
+        assert_eq!("get_root", super::endpoint_name("GET", "/"));
+        assert_eq!(
+            "post_users_id_avatar_png",
+            super::endpoint_name("POST", "/users/{id}/avatar.png")
+        );
+    }
+
+    #[test]
+    fn har_examples() {
+        let har = json!({
+            "log": {
+                "entries": [
+                    {
+                        "request": {
+                            "method": "POST",
+                            "url": "https://example.com/users",
+                            "postData": { "mimeType": "application/json", "text": "{\"name\":\"a\"}" }
+                        },
+                        "response": {
+                            "content": { "mimeType": "application/json; charset=utf-8", "text": "{\"id\":1}" }
+                        }
+                    },
+                    {
+                        "request": { "method": "GET", "url": "https://example.com/" },
+                        "response": {
+                            "content": { "mimeType": "text/html", "text": "<html></html>" }
+                        }
+                    },
+                    {
+                        "request": { "method": "GET", "url": "https://example.com/users/1" },
+                        "response": {
+                            "content": { "mimeType": "application/json", "text": "e30=", "encoding": "base64" }
+                        }
+                    }
+                ]
+            }
+        });
+
+        assert_eq!(
+            vec![
+                HarExample {
+                    name: "post_users_request".to_owned(),
+                    value: json!({ "name": "a" }),
+                },
+                HarExample {
+                    name: "post_users_response".to_owned(),
+                    value: json!({ "id": 1 }),
+                },
+            ],
+            super::har_examples(&har).unwrap()
+        );
+
+        assert!(super::har_examples(&json!({})).is_err());
+    }
+
+    #[test]
+    fn colliding_endpoint_names() {
+        let entry = |method: &str, url: &str| {
+            json!({
+                "request": { "method": method, "url": url },
+                "response": { "content": { "mimeType": "application/json", "text": "{}" }},
+            })
+        };
+
+        let har = json!({
+            "log": {
+                "entries": [
+                    entry("GET", "https://example.com/users-list"),
+                    entry("GET", "https://example.com/users_list"),
+                    entry("get", "https://example.com/users-list?page=2"),
+                    entry("GET", "https://example.com/users/id"),
+                    entry("GET", "https://example.com/users/42"),
+                ]
+            }
+        });
+
+        let names: Vec<_> = super::har_examples(&har)
+            .unwrap()
+            .into_iter()
+            .map(|example| example.name)
+            .collect();
+
+        assert_eq!(
+            vec![
+                "get_users_list_response",
+                "get_users_list2_response",
+                "get_users_list_response",
+                "get_users_id_response",
+                "get_users_id2_response",
+            ],
+            names
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::inferred_number::NumType;
+use crate::inferred_number::{Headroom, NumType, NumTypeRules};
+use std::cell::Cell;
+use std::rc::Rc;
 
 /// Hints for [`Inferrer`][`crate::Inferrer`].
 ///
@@ -46,11 +48,38 @@ use crate::inferred_number::NumType;
 ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
 /// )
 /// ```
+///
+/// Besides the hints passed to [`Hints::new`], there are a few global options
+/// that can be enabled with the `with_*` methods on [`Hints`].
+#[derive(Clone)]
 pub struct Hints<'a> {
     default_num_type: NumType,
     enums: HintSet<'a>,
     values: HintSet<'a>,
     discriminator: HintSet<'a>,
+    extended_json: bool,
+    json_strings: bool,
+    json_string_hints: HintSet<'a>,
+    stats_key: Option<&'a str>,
+    stats_samples: usize,
+    redact_samples: bool,
+    redact_hints: HintSet<'a>,
+    descriptions: HintMap<'a, String>,
+    int64_metadata: bool,
+    float32: bool,
+    allowed_num_types: &'a [NumType],
+    num_type_hints: HintMap<'a, NumType>,
+    headroom: Headroom,
+    float_notation: bool,
+    decimal_metadata: bool,
+    type_hints: HintMap<'a, TypeHint>,
+    timestamps: bool,
+    timestamp_hints: HintSet<'a>,
+    no_timestamp_hints: HintSet<'a>,
+    min_timestamp_samples: usize,
+    nullable_hints: HintSet<'a>,
+    optional_hints: HintSet<'a>,
+    required_hints: HintSet<'a>,
 }
 
 impl<'a> Hints<'a> {
@@ -66,20 +95,948 @@ impl<'a> Hints<'a> {
             enums,
             values,
             discriminator,
+            extended_json: false,
+            json_strings: false,
+            json_string_hints: HintSet::new(vec![]),
+            stats_key: None,
+            stats_samples: 3,
+            redact_samples: false,
+            redact_hints: HintSet::new(vec![]),
+            descriptions: HintMap::new(vec![]),
+            int64_metadata: false,
+            float32: false,
+            allowed_num_types: &[],
+            num_type_hints: HintMap::new(vec![]),
+            headroom: Headroom::None,
+            float_notation: false,
+            decimal_metadata: false,
+            type_hints: HintMap::new(vec![]),
+            timestamps: true,
+            timestamp_hints: HintSet::new(vec![]),
+            no_timestamp_hints: HintSet::new(vec![]),
+            min_timestamp_samples: 1,
+            nullable_hints: HintSet::new(vec![]),
+            optional_hints: HintSet::new(vec![]),
+            required_hints: HintSet::new(vec![]),
+        }
+    }
+
+    /// Sets whether to unwrap MongoDB Extended JSON values before inference.
+    ///
+    /// When enabled, wrappers like `{"$oid": "..."}` or `{"$date": "..."}` are
+    /// treated as the logical value they represent, rather than as an object.
+    /// `$date` values are inferred as timestamps, and `$oid` values as strings.
+    /// `$numberLong` and `$numberDecimal` values are inferred as strings, with
+    /// an `extendedJsonType` of `int64` or `decimal128` in their metadata.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_extended_json(true))
+    ///     .infer(json!({
+    ///         "_id": { "$oid": "507f1f77bcf86cd799439011" },
+    ///         "createdAt": { "$date": { "$numberLong": "1577836800000" } },
+    ///         "views": { "$numberLong": "9007199254740993" },
+    ///     }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "_id": { "type": "string" },
+    ///             "createdAt": { "type": "timestamp" },
+    ///             "views": {
+    ///                 "type": "string",
+    ///                 "metadata": { "extendedJsonType": "int64" },
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_extended_json(self, extended_json: bool) -> Self {
+        Self {
+            extended_json,
+            ..self
+        }
+    }
+
+    /// Sets whether to look inside of strings that contain JSON-encoded objects
+    /// or arrays, everywhere in the input.
+    ///
+    /// When a string contains an object or array, the schema inferred for its
+    /// contents is attached to the string's schema, as the `encodedJson`
+    /// property of its `metadata`. To only do this for some parts of the input,
+    /// see [`Hints::with_json_string_hints`].
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_json_strings(true))
+    ///     .infer(json!({ "payload": "{\"a\":1}" }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "payload": {
+    ///                 "type": "string",
+    ///                 "metadata": {
+    ///                     "encodedJson": {
+    ///                         "properties": { "a": { "type": "uint8" }},
+    ///                     },
+    ///                 },
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_json_strings(self, json_strings: bool) -> Self {
+        Self {
+            json_strings,
+            ..self
+        }
+    }
+
+    /// Sets the parts of the input where strings containing JSON-encoded
+    /// objects or arrays should be looked inside of.
+    ///
+    /// This is like [`Hints::with_json_strings`], but only applies to the paths
+    /// in `json_string_hints`. Paths to parts of the encoded JSON continue from
+    /// the path to the string containing it.
+    pub fn with_json_string_hints(self, json_string_hints: HintSet<'a>) -> Self {
+        Self {
+            json_string_hints,
+            ..self
+        }
+    }
+
+    /// Sets a `metadata` property to put statistics about the examples in.
+    ///
+    /// When set, the schema for each part of the input gets an object in its
+    /// `metadata`, under `metadata_key`, that holds:
+    ///
+    /// * `count`, the number of examples of that part of the input,
+    /// * `nullRatio`, the proportion of those examples that were null, if any,
+    /// * `min` and `max`, the smallest and largest numbers seen, if any,
+    /// * `minLength` and `maxLength`, the shortest and longest strings seen, in
+    ///   characters, if any, and
+    /// * `samples`, a few of the distinct strings, numbers and booleans seen.
+    ///   Long strings are truncated.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_stats("stats").with_stats_samples(2))
+    ///     .infer(json!(["foo", "ba", null, "x"]))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "elements": {
+    ///             "type": "string",
+    ///             "nullable": true,
+    ///             "metadata": {
+    ///                 "stats": {
+    ///                     "count": 4,
+    ///                     "nullRatio": 0.25,
+    ///                     "minLength": 1,
+    ///                     "maxLength": 3,
+    ///                     "samples": ["foo", "ba"],
+    ///                 },
+    ///             },
+    ///         },
+    ///         "metadata": { "stats": { "count": 1 }},
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_stats(self, metadata_key: &'a str) -> Self {
+        Self {
+            stats_key: Some(metadata_key),
+            ..self
+        }
+    }
+
+    /// Sets how many distinct samples to keep for each part of the input, when
+    /// [`Hints::with_stats`] is enabled. The default is 3.
+    pub fn with_stats_samples(self, stats_samples: usize) -> Self {
+        Self {
+            stats_samples,
+            ..self
+        }
+    }
+
+    /// Sets whether to leave samples, as well as the smallest and largest
+    /// numbers and lengths, out of the statistics from [`Hints::with_stats`]
+    /// everywhere in the input, so that they don't reveal any of the actual
+    /// data. Only `count` and `nullRatio` are kept.
+    pub fn with_redacted_samples(self, redact_samples: bool) -> Self {
+        Self {
+            redact_samples,
+            ..self
+        }
+    }
+
+    /// Sets the parts of the input to leave samples out of the statistics
+    /// for. This is like [`Hints::with_redacted_samples`], but only applies to
+    /// the paths in `redact_hints`, and everything inside of them.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let path = vec!["user".to_string()];
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let hints = hints
+    ///     .with_stats("stats")
+    ///     .with_redact_hints(HintSet::new(vec![&path]));
+    ///
+    /// let inference = Inferrer::new(hints)
+    ///     .infer(json!({ "user": { "email": "a@b.c" }, "id": 3 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "id": {
+    ///                 "type": "uint8",
+    ///                 "metadata": { "stats": { "count": 1, "min": 3, "max": 3, "samples": [3] }},
+    ///             },
+    ///             "user": {
+    ///                 "properties": {
+    ///                     "email": {
+    ///                         "type": "string",
+    ///                         "metadata": { "stats": { "count": 1 }},
+    ///                     },
+    ///                 },
+    ///                 "metadata": { "stats": { "count": 1 }},
+    ///             },
+    ///         },
+    ///         "metadata": { "stats": { "count": 1 }},
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_redact_hints(self, redact_hints: HintSet<'a>) -> Self {
+        Self {
+            redact_hints,
+            ..self
+        }
+    }
+
+    /// Sets descriptions for parts of the input, which are put in the
+    /// `description` property of the `metadata` of the corresponding parts of
+    /// the inferred schema.
+    ///
+    /// Use [`HintMap::unmatched`] afterwards to find any descriptions that
+    /// weren't used, because nothing in the input matched their path.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintMap, HintSet, NumType};
+    ///
+    /// let path1 = vec!["id".to_string()];
+    /// let path2 = vec!["tags".to_string(), "-".to_string()];
+    /// let path3 = vec!["name".to_string()];
+    /// let (desc1, desc2, desc3) = ("The ID".to_string(), "A tag".to_string(), "??".to_string());
+    /// let descriptions = HintMap::new(vec![(&path1, &desc1), (&path2, &desc2), (&path3, &desc3)]);
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_descriptions(descriptions.clone()))
+    ///     .infer(json!({ "id": 1, "tags": ["a"] }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "id": { "type": "uint8", "metadata": { "description": "The ID" }},
+    ///             "tags": {
+    ///                 "elements": { "type": "string", "metadata": { "description": "A tag" }},
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// );
+    ///
+    /// assert_eq!(vec![2], descriptions.unmatched());
+    /// ```
+    pub fn with_descriptions(self, descriptions: HintMap<'a, String>) -> Self {
+        Self {
+            descriptions,
+            ..self
+        }
+    }
+
+    /// Sets whether to mark integers that are too large for JSON Typedef's
+    /// integer types as 64-bit integers, for the sake of code generators.
+    ///
+    /// JSON Typedef has no 64-bit integer types, so such integers are inferred
+    /// as `float64`, which can't precisely represent integers beyond 2^53.
+    /// When enabled, their schema gets `rustType`, `goType` and
+    /// `typescriptType` properties in its `metadata`, which tell code
+    /// generators to use a 64-bit integer type instead.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_int64_metadata(true))
+    ///     .infer(json!({ "id": 9007199254740993u64 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "id": {
+    ///                 "type": "float64",
+    ///                 "metadata": {
+    ///                     "goType": "int64",
+    ///                     "rustType": "i64",
+    ///                     "typescriptType": "bigint",
+    ///                 },
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_int64_metadata(self, int64_metadata: bool) -> Self {
+        Self {
+            int64_metadata,
+            ..self
+        }
+    }
+
+    /// Sets whether to infer `float32`, rather than `float64`, for numbers
+    /// that all survive a round-trip through a 32-bit float.
+    ///
+    /// A number survives the round-trip if converting it to the nearest 32-bit
+    /// float, and writing that out as the shortest decimal that identifies it,
+    /// gives back the same number. For example, `0.1` does, but `0.1234567891`
+    /// doesn't. This only matters for numbers that don't fit the default number
+    /// type or any integer type.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_float32(true))
+    ///     .infer(json!({ "temp": 21.5, "lat": 52.379189 }))
+    ///     .infer(json!({ "temp": 19.25, "lat": 4.899431 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "lat": { "type": "float64" },
+    ///             "temp": { "type": "float32" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_float32(self, float32: bool) -> Self {
+        Self { float32, ..self }
+    }
+
+    /// Sets the number types that may be inferred. If empty, which is the
+    /// default, then any number type may be inferred.
+    ///
+    /// Numbers are inferred as the default number type if it's allowed and
+    /// contains them, or else as the narrowest allowed type that does. If none
+    /// of the allowed types contain them, then they're inferred as the widest
+    /// allowed type, even though it doesn't accept all of the examples.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// // For example, Java has no unsigned integer types.
+    /// let allowed = [NumType::Int32, NumType::Float64];
+    /// let inference = Inferrer::new(hints.with_allowed_num_types(&allowed))
+    ///     .infer(json!({ "count": 12, "price": 4.99 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "count": { "type": "int32" },
+    ///             "price": { "type": "float64" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_allowed_num_types(self, allowed_num_types: &'a [NumType]) -> Self {
+        Self {
+            allowed_num_types,
+            ..self
+        }
+    }
+
+    /// Sets the default number type for parts of the input, overriding
+    /// `default_num_type` for them.
+    ///
+    /// Like `default_num_type`, the default for a part of the input is ignored
+    /// if it doesn't contain the numbers there. Use [`HintMap::unmatched`]
+    /// afterwards to find any hints whose path didn't match any numbers.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintMap, HintSet, NumType};
+    ///
+    /// let path = vec!["-".to_string(), "id".to_string()];
+    /// let num_type_hints = HintMap::new(vec![(&path, &NumType::Int32)]);
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_num_type_hints(num_type_hints))
+    ///     .infer(json!({ "user": { "id": 1, "age": 30 }}))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "user": {
+    ///                 "properties": {
+    ///                     "age": { "type": "uint8" },
+    ///                     "id": { "type": "int32" },
+    ///                 },
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_num_type_hints(self, num_type_hints: HintMap<'a, NumType>) -> Self {
+        Self {
+            num_type_hints,
+            ..self
+        }
+    }
+
+    /// Sets how much larger than the integers in the examples the inferred
+    /// integer types must be. See [`Headroom`] for the options. The default is
+    /// [`Headroom::None`].
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Headroom, Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_headroom(Headroom::NextSize))
+    ///     .infer(json!({ "count": 200, "delta": -5 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "count": { "type": "uint16" },
+    ///             "delta": { "type": "int16" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_headroom(self, headroom: Headroom) -> Self {
+        Self { headroom, ..self }
+    }
+
+    /// Sets whether numbers written with a fraction or exponent, like `1.0` or
+    /// `1e3`, are inferred as floats even when they're whole.
+    ///
+    /// By default, only the value of a number matters, so `1.0` is inferred
+    /// the same way as `1`. Producers that write `1.0` usually mean a float
+    /// that happened to be whole, though, so with this enabled a number is
+    /// inferred as a float type if any of its examples is written that way.
+    ///
+    /// Unless this crate's `arbitrary_precision` feature is enabled, integers
+    /// too large for 64 bits are treated as if they were written as floats.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inference = Inferrer::new(hints.with_float_notation(true))
+    ///     .infer(serde_json::from_str(r#"{ "count": 3, "price": 1.0 }"#).unwrap())
+    ///     .infer(serde_json::from_str(r#"{ "count": 4, "price": 2 }"#).unwrap())
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "count": { "type": "uint8" },
+    ///             "price": { "type": "float64" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_float_notation(self, float_notation: bool) -> Self {
+        Self {
+            float_notation,
+            ..self
+        }
+    }
+
+    /// Sets whether to add the precision and scale of numbers, as a decimal
+    /// type would need them, to their `metadata`.
+    ///
+    /// The precision is the most significant digits seen, and the scale the
+    /// most digits after the decimal point, both counted as the numbers were
+    /// written, so `12.50` has a precision of 4 and a scale of 2. Trailing zeros
+    /// are only counted if this crate's `arbitrary_precision` feature is
+    /// enabled; otherwise, `12.50` is counted like `12.5`. These go in a
+    /// `decimal` object in the metadata. Numbers that look like amounts of
+    /// money, because they have at most, and sometimes exactly, two decimal
+    /// places, are reported by [`Inferrer::warnings`][`crate::Inferrer::warnings`]
+    /// if they're inferred as a float type, which can't represent most such
+    /// amounts exactly.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inferrer = Inferrer::new(hints.with_decimal_metadata(true))
+    ///     .infer(serde_json::from_str(r#"{ "price": 12.99 }"#).unwrap())
+    ///     .infer(serde_json::from_str(r#"{ "price": 1040.5 }"#).unwrap());
+    ///
+    /// assert_eq!(
+    ///     vec!["/price: numbers look like amounts of money, with up to 2 decimal places, but float64 can't represent most of those exactly; consider representing them as decimal strings"],
+    ///     inferrer.warnings(),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "price": {
+    ///                 "type": "float64",
+    ///                 "metadata": { "decimal": { "precision": 6, "scale": 2 }},
+    ///             },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_decimal_metadata(self, decimal_metadata: bool) -> Self {
+        Self {
+            decimal_metadata,
+            ..self
+        }
+    }
+
+    /// Sets the type to infer for parts of the input, regardless of their
+    /// examples.
+    ///
+    /// Examples that aren't of the hinted type don't widen it, the way they
+    /// otherwise would. Instead, [`Inferrer::warnings`][`crate::Inferrer::warnings`]
+    /// reports them. Nulls never contradict a hint; they make the type nullable
+    /// as usual. Use [`HintMap::unmatched`] afterwards to find any hints whose
+    /// path didn't match any part of the input.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintMap, HintSet, NumType, TypeHint};
+    ///
+    /// let path = vec!["updated".to_string()];
+    /// let type_hints = HintMap::new(vec![(&path, &TypeHint::String)]);
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inferrer = Inferrer::new(hints.with_type_hints(type_hints))
+    ///     .infer(json!({ "updated": "2021-01-01T00:00:00Z" }))
+    ///     .infer(json!({ "updated": 1609459200 }));
+    ///
+    /// assert_eq!(
+    ///     vec!["/updated: 1 example contradicts the type hint string, such as 1609459200"],
+    ///     inferrer.warnings(),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     json!({ "properties": { "updated": { "type": "string" }}}),
+    ///     serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_type_hints(self, type_hints: HintMap<'a, TypeHint>) -> Self {
+        Self { type_hints, ..self }
+    }
+
+    /// Sets whether to infer `timestamp` for strings that are all RFC 3339
+    /// timestamps. The default is true.
+    ///
+    /// A sample that only happens to contain dates can make a string look like
+    /// a timestamp, and checking every string for a timestamp takes time.
+    /// Disabling this infers all strings as `string`, except in the parts of
+    /// the input in [`Hints::with_timestamp_hints`].
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let path = vec!["created".to_string()];
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let hints = hints
+    ///     .with_timestamps(false)
+    ///     .with_timestamp_hints(HintSet::new(vec![&path]));
+    ///
+    /// let inference = Inferrer::new(hints)
+    ///     .infer(json!({
+    ///         "created": "2021-01-01T00:00:00Z",
+    ///         "name": "2021-01-01T00:00:00Z",
+    ///     }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "created": { "type": "timestamp" },
+    ///             "name": { "type": "string" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_timestamps(self, timestamps: bool) -> Self {
+        Self { timestamps, ..self }
+    }
+
+    /// Sets the parts of the input to infer `timestamp` for, if their strings
+    /// are all timestamps, even if [`Hints::with_timestamps`] is disabled.
+    pub fn with_timestamp_hints(self, timestamp_hints: HintSet<'a>) -> Self {
+        Self {
+            timestamp_hints,
+            ..self
+        }
+    }
+
+    /// Sets the parts of the input to never infer `timestamp` for. This takes
+    /// precedence over [`Hints::with_timestamps`] and
+    /// [`Hints::with_timestamp_hints`].
+    pub fn with_no_timestamp_hints(self, no_timestamp_hints: HintSet<'a>) -> Self {
+        Self {
+            no_timestamp_hints,
+            ..self
+        }
+    }
+
+    /// Sets how many timestamps a part of the input must have before it's
+    /// inferred as a `timestamp`, rather than a `string`. The default is 1.
+    pub fn with_min_timestamp_samples(self, min_timestamp_samples: usize) -> Self {
+        Self {
+            min_timestamp_samples,
+            ..self
+        }
+    }
+
+    /// Sets the parts of the input to make nullable, even if none of their
+    /// examples are null.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let nullable_path = vec!["nickname".to_string()];
+    /// let optional_path = vec!["age".to_string()];
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let hints = hints
+    ///     .with_nullable_hints(HintSet::new(vec![&nullable_path]))
+    ///     .with_optional_hints(HintSet::new(vec![&optional_path]));
+    ///
+    /// let inference = Inferrer::new(hints)
+    ///     .infer(json!({ "nickname": "bob", "age": 30 }))
+    ///     .into_schema();
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "nickname": { "type": "string", "nullable": true },
+    ///         },
+    ///         "optionalProperties": {
+    ///             "age": { "type": "uint8" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inference.into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_nullable_hints(self, nullable_hints: HintSet<'a>) -> Self {
+        Self {
+            nullable_hints,
+            ..self
+        }
+    }
+
+    /// Sets the properties to make optional, even if every example has them.
+    /// This takes precedence over [`Hints::with_required_hints`].
+    pub fn with_optional_hints(self, optional_hints: HintSet<'a>) -> Self {
+        Self {
+            optional_hints,
+            ..self
+        }
+    }
+
+    /// Sets the properties to keep required, even if some examples don't have
+    /// them.
+    ///
+    /// [`Inferrer::warnings`][`crate::Inferrer::warnings`] reports how many
+    /// examples were missing each of these properties, and which was the first
+    /// of them, counting from 1.
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jtd_infer::{Inferrer, Hints, HintSet, NumType};
+    ///
+    /// let path = vec!["id".to_string()];
+    ///
+    /// let hints = Hints::new(
+    ///     NumType::Uint8,
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    ///     HintSet::new(vec![]),
+    /// );
+    ///
+    /// let inferrer = Inferrer::new(hints.with_required_hints(HintSet::new(vec![&path])))
+    ///     .infer(json!({ "name": "bob" }))
+    ///     .infer(json!({ "id": 1, "name": "alice" }));
+    ///
+    /// assert_eq!(
+    ///     vec!["/id: required property is missing from 1 of 2 examples, starting with example 1"],
+    ///     inferrer.warnings(),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "properties": {
+    ///             "id": { "type": "uint8" },
+    ///             "name": { "type": "string" },
+    ///         },
+    ///     }),
+    ///     serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap(),
+    /// )
+    /// ```
+    pub fn with_required_hints(self, required_hints: HintSet<'a>) -> Self {
+        Self {
+            required_hints,
+            ..self
         }
     }
 
-    pub(crate) fn default_num_type(&self) -> &NumType {
-        &self.default_num_type
+    pub(crate) fn num_type_rules(&self) -> NumTypeRules<'a> {
+        NumTypeRules {
+            default: self
+                .num_type_hints
+                .get_active()
+                .copied()
+                .unwrap_or(self.default_num_type),
+            allowed: self.allowed_num_types,
+            float32: self.float32,
+            headroom: self.headroom,
+            float_notation: self.float_notation,
+        }
     }
 
     pub(crate) fn sub_hints(&self, key: &str) -> Self {
-        Self::new(
-            self.default_num_type.clone(),
-            self.enums.sub_hints(key),
-            self.values.sub_hints(key),
-            self.discriminator.sub_hints(key),
-        )
+        self.sub_hints_by(&|first| first == key)
+    }
+
+    /// Returns the hints for every element of an array, as a whole. Paths to
+    /// any index of the array, as well as wildcards, apply to its elements.
+    pub(crate) fn element_hints(&self) -> Self {
+        self.sub_hints_by(&|first| first.parse::<usize>().is_ok())
+    }
+
+    /// Returns the hints for every value of an object of the values form, as a
+    /// whole. Paths to any key of the object, as well as wildcards, apply to
+    /// its values.
+    pub(crate) fn value_hints(&self) -> Self {
+        self.sub_hints_by(&|_| true)
+    }
+
+    /// Returns the hints for the parts of the input whose key, or array index,
+    /// is `matches`. Wildcards always match.
+    fn sub_hints_by(&self, matches: &dyn Fn(&str) -> bool) -> Self {
+        Self {
+            default_num_type: self.default_num_type,
+            enums: self.enums.sub_hints_by(matches),
+            values: self.values.sub_hints_by(matches),
+            discriminator: self.discriminator.sub_hints_by(matches),
+            extended_json: self.extended_json,
+            json_strings: self.json_strings,
+            json_string_hints: self.json_string_hints.sub_hints_by(matches),
+            stats_key: self.stats_key,
+            stats_samples: self.stats_samples,
+            // Once a part of the input is redacted, so is everything in it.
+            redact_samples: self.is_redacted(),
+            redact_hints: self.redact_hints.sub_hints_by(matches),
+            descriptions: self.descriptions.sub_hints_by(matches),
+            int64_metadata: self.int64_metadata,
+            float32: self.float32,
+            allowed_num_types: self.allowed_num_types,
+            num_type_hints: self.num_type_hints.sub_hints_by(matches),
+            headroom: self.headroom,
+            float_notation: self.float_notation,
+            decimal_metadata: self.decimal_metadata,
+            type_hints: self.type_hints.sub_hints_by(matches),
+            timestamps: self.timestamps,
+            timestamp_hints: self.timestamp_hints.sub_hints_by(matches),
+            no_timestamp_hints: self.no_timestamp_hints.sub_hints_by(matches),
+            min_timestamp_samples: self.min_timestamp_samples,
+            nullable_hints: self.nullable_hints.sub_hints_by(matches),
+            optional_hints: self.optional_hints.sub_hints_by(matches),
+            required_hints: self.required_hints.sub_hints_by(matches),
+        }
+    }
+
+    pub(crate) fn is_extended_json(&self) -> bool {
+        self.extended_json
+    }
+
+    pub(crate) fn is_int64_metadata(&self) -> bool {
+        self.int64_metadata
+    }
+
+    pub(crate) fn is_decimal_metadata(&self) -> bool {
+        self.decimal_metadata
+    }
+
+    pub(crate) fn is_timestamp_active(&self) -> bool {
+        if self.no_timestamp_hints.is_active() {
+            false
+        } else {
+            self.timestamps || self.timestamp_hints.is_active()
+        }
+    }
+
+    pub(crate) fn is_nullable_active(&self) -> bool {
+        self.nullable_hints.is_active()
+    }
+
+    pub(crate) fn is_optional_active(&self) -> bool {
+        self.optional_hints.is_active()
+    }
+
+    pub(crate) fn is_required_active(&self) -> bool {
+        self.required_hints.is_active()
+    }
+
+    /// Whether the property `key` is kept required by a required hint, which
+    /// an optional hint doesn't override.
+    pub(crate) fn keeps_required(&self, key: &str) -> bool {
+        let matches = |first: &str| first == key;
+
+        self.required_hints.sub_hints_by(&matches).is_active()
+            && !self.optional_hints.sub_hints_by(&matches).is_active()
+    }
+
+    pub(crate) fn min_timestamp_samples(&self) -> usize {
+        self.min_timestamp_samples
+    }
+
+    pub(crate) fn type_hint(&self) -> Option<TypeHint> {
+        self.type_hints.get_active().copied()
     }
 
     pub(crate) fn is_enum_active(&self) -> bool {
@@ -90,15 +1047,61 @@ impl<'a> Hints<'a> {
         self.values.is_active()
     }
 
+    pub(crate) fn is_json_string_active(&self) -> bool {
+        self.json_strings || self.json_string_hints.is_active()
+    }
+
+    pub(crate) fn stats_key(&self) -> Option<&str> {
+        self.stats_key
+    }
+
+    pub(crate) fn is_redacted(&self) -> bool {
+        self.redact_samples || self.redact_hints.is_active()
+    }
+
+    pub(crate) fn max_samples(&self) -> usize {
+        if self.is_redacted() {
+            0
+        } else {
+            self.stats_samples
+        }
+    }
+
+    pub(crate) fn description(&self) -> Option<&'a String> {
+        self.descriptions.get_active()
+    }
+
     pub(crate) fn peek_active_discriminator(&self) -> Option<&str> {
         self.discriminator.peek_active()
     }
 }
 
-const WILDCARD: &'static str = "-";
+pub(crate) const WILDCARD: &str = "-";
+
+/// A type to infer for a part of the input, regardless of its examples.
+///
+/// See [`Hints::with_type_hints`] for how this enum is used.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TypeHint {
+    /// Corresponds to [`jtd::Type::String`].
+    String,
+
+    /// Corresponds to [`jtd::Type::Timestamp`].
+    Timestamp,
+
+    /// Corresponds to [`jtd::Type::Boolean`].
+    Boolean,
+
+    /// Corresponds to the type of the given [`NumType`].
+    Number(NumType),
+
+    /// Corresponds to the empty form, which accepts any value.
+    Any,
+}
 
 /// A set of paths to parts of the input that are subject to a hint in
 /// [`Hints`].
+#[derive(Clone)]
 pub struct HintSet<'a> {
     values: Vec<&'a [String]>,
 }
@@ -126,13 +1129,19 @@ impl<'a> HintSet<'a> {
         HintSet { values }
     }
 
-    pub(crate) fn sub_hints(&self, key: &str) -> Self {
+    #[cfg(test)]
+    fn sub_hints(&self, key: &str) -> Self {
+        self.sub_hints_by(&|first| first == key)
+    }
+
+    pub(crate) fn sub_hints_by(&self, matches: &dyn Fn(&str) -> bool) -> Self {
         Self::new(
             self.values
                 .iter()
                 .filter(|values| {
-                    let first = values.first().map(String::as_str);
-                    first == Some(WILDCARD) || first == Some(key)
+                    values
+                        .first()
+                        .is_some_and(|first| first == WILDCARD || matches(first))
                 })
                 .map(|values| &values[1..])
                 .collect(),
@@ -151,9 +1160,93 @@ impl<'a> HintSet<'a> {
     }
 }
 
+/// A set of paths to parts of the input, each with a value, for hints in
+/// [`Hints`] that need more than a path.
+///
+/// Paths work the same way as in [`HintSet`]. If several paths match the same
+/// part of the input, the value of the last one is used.
+pub struct HintMap<'a, T> {
+    values: Vec<(&'a [String], &'a T, usize)>,
+
+    /// Whether each of the values passed to [`HintMap::new`] has been used.
+    /// This is shared with the [`HintMap`]s for sub-paths.
+    matched: Rc<Vec<Cell<bool>>>,
+}
+
+impl<'a, T> HintMap<'a, T> {
+    /// Constructs a new [`HintMap`] from a list of paths and their values.
+    pub fn new(values: Vec<(&'a [String], &'a T)>) -> Self {
+        HintMap {
+            matched: Rc::new(values.iter().map(|_| Cell::new(false)).collect()),
+            values: values
+                .into_iter()
+                .enumerate()
+                .map(|(i, (path, value))| (path, value, i))
+                .collect(),
+        }
+    }
+
+    /// Returns the indices of the values passed to [`HintMap::new`] whose path
+    /// hasn't matched any part of an inferred schema.
+    ///
+    /// Clones of a [`HintMap`], including the ones in clones of [`Hints`],
+    /// share which of their paths have matched.
+    pub fn unmatched(&self) -> Vec<usize> {
+        self.matched
+            .iter()
+            .enumerate()
+            .filter(|(_, matched)| !matched.get())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    #[cfg(test)]
+    fn sub_hints(&self, key: &str) -> Self {
+        self.sub_hints_by(&|first| first == key)
+    }
+
+    pub(crate) fn sub_hints_by(&self, matches: &dyn Fn(&str) -> bool) -> Self {
+        HintMap {
+            values: self
+                .values
+                .iter()
+                .filter(|(path, _, _)| {
+                    path.first()
+                        .is_some_and(|first| first == WILDCARD || matches(first))
+                })
+                .map(|(path, value, i)| (&path[1..], *value, *i))
+                .collect(),
+            matched: self.matched.clone(),
+        }
+    }
+
+    pub(crate) fn get_active(&self) -> Option<&'a T> {
+        let mut active = None;
+        for (path, value, i) in &self.values {
+            if path.is_empty() {
+                self.matched[*i].set(true);
+                active = Some(*value);
+            }
+        }
+
+        active
+    }
+}
+
+// Deriving Clone would needlessly require that T be Clone.
+impl<'a, T> Clone for HintMap<'a, T> {
+    fn clone(&self) -> Self {
+        HintMap {
+            values: self.values.clone(),
+            matched: self.matched.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn hint_set() {
@@ -205,4 +1298,223 @@ mod tests {
             .sub_hints("e")
             .is_active());
     }
+
+    #[test]
+    fn hint_map() {
+        let path1 = vec!["a".to_string(), "-".to_string()];
+        let path2 = vec!["a".to_string(), "b".to_string()];
+        let path3 = vec!["c".to_string()];
+        let hint_map = HintMap::new(vec![(&path1, &1), (&path2, &2), (&path3, &3)]);
+
+        assert_eq!(None, hint_map.get_active());
+        assert_eq!(None, hint_map.sub_hints("a").get_active());
+        assert_eq!(
+            Some(&1),
+            hint_map.sub_hints("a").sub_hints("x").get_active()
+        );
+        assert_eq!(vec![1, 2], hint_map.unmatched());
+
+        assert_eq!(
+            Some(&2),
+            hint_map.sub_hints("a").sub_hints("b").get_active()
+        );
+        assert_eq!(vec![2], hint_map.unmatched());
+    }
+
+    #[test]
+    fn element_hints() {
+        let path = vec!["tags".to_string(), "0".to_string()];
+        let description = "A tag".to_string();
+        let descriptions = HintMap::new(vec![(&path, &description)]);
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_descriptions(descriptions.clone());
+
+        assert_eq!(None, hints.sub_hints("tags").sub_hints("1").description());
+        assert_eq!(
+            Some(&description),
+            hints.sub_hints("tags").element_hints().description()
+        );
+        assert!(descriptions.unmatched().is_empty());
+    }
+
+    #[test]
+    fn redact_hints() {
+        let path = vec!["user".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_redact_hints(HintSet::new(vec![&path]));
+
+        assert!(!hints.is_redacted());
+        assert!(!hints.sub_hints("id").is_redacted());
+        assert!(hints.sub_hints("user").is_redacted());
+        assert!(hints.sub_hints("user").sub_hints("email").is_redacted());
+        assert!(hints
+            .sub_hints("user")
+            .sub_hints("-")
+            .sub_hints("name")
+            .is_redacted());
+    }
+
+    #[test]
+    fn required_and_optional_hints() {
+        let path = vec!["id".to_string()];
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_required_hints(HintSet::new(vec![&path]));
+
+        fn infer(hints: Hints) -> crate::Inferrer {
+            crate::Inferrer::new(hints)
+                .infer(json!({ "id": 1 }))
+                .infer(json!({}))
+                .infer(json!({ "id": 2 }))
+                .infer(json!({}))
+        }
+
+        let inferrer = infer(hints.clone());
+        assert_eq!(
+            vec!["/id: required property is missing from 2 of 4 examples, starting with example 2"],
+            inferrer.warnings()
+        );
+        assert_eq!(
+            json!({ "properties": { "id": { "type": "uint8" }}}),
+            serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap()
+        );
+
+        // The optional hint wins, so the omissions aren't worth a warning.
+        let inferrer = infer(hints.with_optional_hints(HintSet::new(vec![&path])));
+        assert!(inferrer.warnings().is_empty());
+        assert_eq!(
+            json!({ "optionalProperties": { "id": { "type": "uint8" }}}),
+            serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap()
+        );
+    }
+
+    #[test]
+    fn number_type_hints() {
+        let path = vec!["x".to_string()];
+        let float32 = TypeHint::Number(NumType::Float32);
+        let float64 = TypeHint::Number(NumType::Float64);
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        );
+
+        let inferrer = crate::Inferrer::new(
+            hints
+                .clone()
+                .with_type_hints(HintMap::new(vec![(&path, &float32)])),
+        )
+        .infer(json!({ "x": 0.5 }))
+        .infer(json!({ "x": 0.123456789 }));
+        assert_eq!(
+            vec!["/x: 1 example contradicts the type hint float32, such as 0.123456789"],
+            inferrer.warnings()
+        );
+
+        let inferrer = crate::Inferrer::new(
+            hints
+                .with_int64_metadata(true)
+                .with_type_hints(HintMap::new(vec![(&path, &float64)])),
+        )
+        .infer(json!({ "x": 0.5 }))
+        .infer(json!({ "x": 9007199254740993u64 }));
+        assert_eq!(
+            vec!["/x: 1 example contradicts the type hint float64, such as 9007199254740993"],
+            inferrer.warnings()
+        );
+    }
+
+    #[test]
+    fn type_hint_with_only_nulls() {
+        let path = vec!["a".to_string()];
+        let string = TypeHint::String;
+        let type_hints = HintMap::new(vec![(&path, &string)]);
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_type_hints(type_hints.clone());
+
+        let inferrer = crate::Inferrer::new(hints)
+            .infer(json!({ "a": null }))
+            .infer(json!({ "a": null }));
+
+        assert!(inferrer.warnings().is_empty());
+        assert_eq!(
+            json!({ "properties": { "a": { "type": "string", "nullable": true }}}),
+            serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap()
+        );
+        assert!(type_hints.unmatched().is_empty());
+    }
+
+    #[test]
+    fn type_hint_with_encoded_numbers() {
+        let path = vec!["n".to_string()];
+        let boolean = TypeHint::Boolean;
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_extended_json(true)
+        .with_stats("stats")
+        .with_type_hints(HintMap::new(vec![(&path, &boolean)]));
+
+        let inferrer = crate::Inferrer::new(hints)
+            .infer(json!({ "n": { "$numberLong": "42" }}))
+            .infer(json!({ "n": true }));
+
+        assert_eq!(
+            vec!["/n: 1 example contradicts the type hint boolean, such as \"42\""],
+            inferrer.warnings()
+        );
+        assert_eq!(
+            json!({ "count": 2, "samples": ["42", true], "minLength": 2, "maxLength": 2 }),
+            serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap()["properties"]
+                ["n"]["metadata"]["stats"]
+        );
+    }
+
+    #[test]
+    fn index_path_hints() {
+        let path = vec!["xs".to_string(), "0".to_string()];
+        let uint16 = TypeHint::Number(NumType::Uint16);
+        let hints = Hints::new(
+            NumType::Uint8,
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+            HintSet::new(vec![]),
+        )
+        .with_type_hints(HintMap::new(vec![(&path, &uint16)]));
+
+        // The hint for the first element applies to the second one too, both
+        // when inferring and in the schema.
+        let inferrer = crate::Inferrer::new(hints).infer(json!({ "xs": [1, "two"] }));
+        assert_eq!(
+            vec!["/xs/-: 1 example contradicts the type hint uint16, such as \"two\""],
+            inferrer.warnings()
+        );
+        assert_eq!(
+            json!({ "properties": { "xs": { "elements": { "type": "uint16" }}}}),
+            serde_json::to_value(inferrer.into_schema().into_serde_schema()).unwrap()
+        );
+    }
 }
@@ -1,66 +1,391 @@
 use jtd::Type;
+use serde_json::Number;
 
 #[derive(Debug)]
 pub struct InferredNumber {
-    min: f64,
-    max: f64,
-    int: bool,
+    /// The smallest and largest integers seen, if any. Integers are tracked
+    /// exactly, rather than as f64, so that large ones can be told apart.
+    int_range: Option<(i128, i128)>,
+
+    /// The smallest and largest non-integers seen, if any.
+    float_range: Option<(f64, f64)>,
+
+    /// Whether every number seen survives a round-trip through f32.
+    float32: bool,
+
+    /// Whether any number seen was written with a fraction or exponent, like
+    /// `1.0` or `1e3`, even if it's whole.
+    float_notation: bool,
+
+    /// The most digits seen before and after the decimal point, as written.
+    int_digits: u32,
+    scale: u32,
 }
 
 impl InferredNumber {
     pub fn new() -> Self {
         Self {
-            min: f64::MAX,
-            max: f64::MIN,
-            int: true,
+            int_range: None,
+            float_range: None,
+            float32: true,
+            float_notation: false,
+            int_digits: 0,
+            scale: 0,
         }
     }
 
-    pub fn infer(&self, n: f64) -> Self {
-        Self {
-            min: self.min.min(n),
-            max: self.max.max(n),
-            int: self.int && n.fract() == 0.0,
+    pub fn infer(&self, n: &Number) -> Self {
+        let float32 = self.float32 && round_trips_f32(n.as_f64().unwrap());
+
+        // Numbers with a fraction or exponent are always f64s, so this tells
+        // `1.0` apart from `1`. Unless the arbitrary_precision feature keeps
+        // the text numbers were written as, integers too large for 64 bits are
+        // f64s too.
+        let float_notation = self.float_notation || n.is_f64();
+
+        // Without the arbitrary_precision feature, this is the shortest text
+        // that gives the same f64, which drops any trailing zeros.
+        let notation = n.to_string();
+        let (int_digits, scale) = decimal_digits(&notation);
+        let (int_digits, scale) = (self.int_digits.max(int_digits), self.scale.max(scale));
+
+        let int = if let Ok(i) = notation.parse::<i128>() {
+            Some(i)
+        } else {
+            // Numbers with a fraction or exponent are only known as an f64.
+            // Whole ones are still integers, as long as they fit in an i128.
+            let f = n.as_f64().unwrap();
+            if f.fract() == 0.0 && f >= i128::MIN as f64 && f < i128::MAX as f64 {
+                Some(f as i128)
+            } else {
+                None
+            }
+        };
+
+        match int {
+            Some(i) => Self {
+                int_range: Some(match self.int_range {
+                    Some((min, max)) => (min.min(i), max.max(i)),
+                    None => (i, i),
+                }),
+                float_range: self.float_range,
+                float32,
+                float_notation,
+                int_digits,
+                scale,
+            },
+            None => {
+                let f = n.as_f64().unwrap();
+                Self {
+                    int_range: self.int_range,
+                    float_range: Some(match self.float_range {
+                        Some((min, max)) => (min.min(f), max.max(f)),
+                        None => (f, f),
+                    }),
+                    float32,
+                    float_notation,
+                    int_digits,
+                    scale,
+                }
+            }
+        }
+    }
+
+    fn is_int(&self) -> bool {
+        self.float_range.is_none()
+    }
+
+    /// Returns whether the numbers are integers that may be inferred as an
+    /// integer type under `rules`.
+    fn is_int_by(&self, rules: &NumTypeRules) -> bool {
+        self.is_int() && !(rules.float_notation && self.float_notation)
+    }
+
+    /// Returns the type to use for the numbers, according to `rules`.
+    ///
+    /// This is the default type, if it contains the numbers, or else the
+    /// narrowest integer type that does. If there is none, then the numbers are
+    /// float64, or float32 if `rules.float32` is set and they all survive a
+    /// round-trip through f32. Only allowed types are considered, and if none
+    /// of them contain the numbers, then the widest allowed type is used.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_type(&self, rules: &NumTypeRules) -> Type {
+        self.choose_type(rules)
+            .unwrap_or_else(|| rules.widest_allowed())
+            .into_type()
+    }
+
+    /// Returns whether any of the allowed types contains the numbers.
+    pub fn fits_allowed(&self, rules: &NumTypeRules) -> bool {
+        self.choose_type(rules).is_some()
+    }
+
+    /// Returns whether `type_` holds the numbers, according to `rules`. Integer
+    /// types must contain them, and float32 must survive a round-trip of them,
+    /// as for [`InferredNumber::into_type`].
+    pub fn fits(&self, type_: &NumType, rules: &NumTypeRules) -> bool {
+        match type_ {
+            NumType::Float32 => self.float32,
+            _ => self.contained_by(type_, rules),
+        }
+    }
+
+    /// Returns whether the numbers are integers too large for a float64 to
+    /// hold exactly.
+    pub fn is_inexact_f64(&self) -> bool {
+        const MAX_EXACT: i128 = 1 << f64::MANTISSA_DIGITS;
+
+        self.is_int()
+            && self
+                .int_range
+                .is_some_and(|(min, max)| min < -MAX_EXACT || max > MAX_EXACT)
+    }
+
+    /// Returns whether the numbers are all integers, but
+    /// [`InferredNumber::into_type`] falls back to a float type for them,
+    /// because they don't fit in any of the allowed integer types.
+    pub fn is_int_fallback(&self, rules: &NumTypeRules) -> bool {
+        self.is_int_by(rules)
+            && !rules.default.is_float()
+            && self
+                .choose_type(rules)
+                .is_some_and(|type_| type_.is_float())
+    }
+
+    fn choose_type(&self, rules: &NumTypeRules) -> Option<NumType> {
+        let with_headroom =
+            self.choose_type_by(rules, |type_| self.contained_with_headroom(type_, rules));
+
+        let without_headroom = self.choose_type_by(rules, |type_| self.contained_by(type_, rules));
+
+        // Headroom may widen an integer type, but never turn one into a float.
+        // If no integer type has enough headroom, then the widest one that
+        // contains the numbers has the most headroom there is.
+        match (with_headroom, without_headroom) {
+            (with, Some(without))
+                if !without.is_float() && with.filter(|with| !with.is_float()).is_none() =>
+            {
+                self.widest_int(rules)
+            }
+            (with, without) => with.or(without),
+        }
+    }
+
+    /// Returns the widest allowed integer type that contains the numbers,
+    /// preferring unsigned types, as [`InferredNumber::choose_type`] does.
+    fn widest_int(&self, rules: &NumTypeRules) -> Option<NumType> {
+        INT_TYPES
+            .iter()
+            .filter(|type_| rules.is_allowed(type_) && self.contained_by(type_, rules))
+            .fold(None, |widest: Option<&NumType>, type_| match widest {
+                Some(widest) if widest.bits() >= type_.bits() => Some(widest),
+                _ => Some(type_),
+            })
+            .copied()
+    }
+
+    fn choose_type_by(
+        &self,
+        rules: &NumTypeRules,
+        contains: impl Fn(&NumType) -> bool,
+    ) -> Option<NumType> {
+        if rules.is_allowed(&rules.default) && contains(&rules.default) {
+            return Some(rules.default);
+        }
+
+        for type_ in &INT_TYPES {
+            if rules.is_allowed(type_) && contains(type_) {
+                return Some(*type_);
+            }
+        }
+
+        if rules.float32 && self.float32 && rules.is_allowed(&NumType::Float32) {
+            Some(NumType::Float32)
+        } else if rules.is_allowed(&NumType::Float64) {
+            Some(NumType::Float64)
+        } else if rules.is_allowed(&NumType::Float32) {
+            Some(NumType::Float32)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the most significant digits that the numbers need as a decimal
+    /// type, counting digits as written, including trailing zeros.
+    pub fn precision(&self) -> u32 {
+        (self.int_digits + self.scale).max(1)
+    }
+
+    /// Returns the most digits after the decimal point that the numbers need
+    /// as a decimal type, counting digits as written, including trailing zeros.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Returns whether the numbers look like amounts of money, because they're
+    /// written with at most, and sometimes exactly, two decimal places.
+    pub fn is_money(&self) -> bool {
+        self.scale == 2
+    }
+
+    /// Returns the 64-bit integer type that contains the numbers, if they're
+    /// all integers.
+    pub fn wide_int(&self) -> Option<WideInt> {
+        if !self.is_int() {
+            return None;
+        }
+
+        let (min, max) = self.int_range?;
+        if min >= i64::MIN as i128 && max <= i64::MAX as i128 {
+            Some(WideInt::Int64)
+        } else if min >= 0 && max <= u64::MAX as i128 {
+            Some(WideInt::Uint64)
+        } else {
+            None
+        }
+    }
+
+    fn contained_with_headroom(&self, type_: &NumType, rules: &NumTypeRules) -> bool {
+        if type_.is_float() || !self.contained_by(type_, rules) {
+            return self.contained_by(type_, rules);
+        }
+
+        match rules.headroom {
+            Headroom::None => true,
+            Headroom::Factor(factor) => {
+                let (type_min, type_max) = type_.as_range().unwrap();
+                match self.int_range {
+                    Some((min, max)) => {
+                        let min = if min < 0 {
+                            min as f64 * factor
+                        } else {
+                            min as f64
+                        };
+                        let max = if max > 0 {
+                            max as f64 * factor
+                        } else {
+                            max as f64
+                        };
+                        type_min as f64 <= min && type_max as f64 >= max
+                    }
+                    None => true,
+                }
+            }
+            Headroom::NextSize => INT_TYPES
+                .iter()
+                .find(|narrowest| self.contained_by(narrowest, rules))
+                .is_some_and(|narrowest| type_.bits() > narrowest.bits()),
+            Headroom::MinBits(bits) => type_.bits() >= bits,
         }
     }
 
-    pub fn into_type(&self, default: &NumType) -> Type {
-        if self.contained_by(default) {
-            return default.into_type();
+    fn contained_by(&self, type_: &NumType, rules: &NumTypeRules) -> bool {
+        let (type_min, type_max) = match type_.as_range() {
+            Some(range) => range,
+            None => return true,
+        };
+
+        if !self.is_int_by(rules) {
+            return false;
+        }
+
+        match self.int_range {
+            Some((min, max)) => type_min <= min && type_max >= max,
+            None => true,
         }
+    }
+}
 
-        let types = [
+/// The integer types, from narrowest to widest.
+const INT_TYPES: [NumType; 6] = [
+    NumType::Uint8,
+    NumType::Int8,
+    NumType::Uint16,
+    NumType::Int16,
+    NumType::Uint32,
+    NumType::Int32,
+];
+
+/// What [`InferredNumber::into_type`] may choose from.
+pub struct NumTypeRules<'a> {
+    /// The type to use whenever it contains the numbers.
+    pub default: NumType,
+
+    /// The types that may be chosen. If empty, any type may be chosen.
+    pub allowed: &'a [NumType],
+
+    /// Whether to choose float32 over float64 for numbers that survive a
+    /// round-trip through f32.
+    pub float32: bool,
+
+    /// How much larger than the integers seen an integer type must be.
+    pub headroom: Headroom,
+
+    /// Whether numbers written with a fraction or exponent, like `1.0`, are
+    /// floats even if they're whole.
+    pub float_notation: bool,
+}
+
+impl<'a> NumTypeRules<'a> {
+    fn is_allowed(&self, type_: &NumType) -> bool {
+        self.allowed.is_empty() || self.allowed.contains(type_)
+    }
+
+    fn widest_allowed(&self) -> NumType {
+        let widths = [
             NumType::Uint8,
             NumType::Int8,
             NumType::Uint16,
             NumType::Int16,
             NumType::Uint32,
             NumType::Int32,
+            NumType::Float32,
+            NumType::Float64,
         ];
 
-        for type_ in &types {
-            if self.contained_by(type_) {
-                return type_.into_type();
-            }
-        }
-
-        return NumType::Float64.into_type();
+        widths
+            .iter()
+            .rev()
+            .find(|type_| self.is_allowed(type_))
+            .copied()
+            .unwrap_or(NumType::Float64)
     }
+}
 
-    fn contained_by(&self, type_: &NumType) -> bool {
-        if !self.int && !type_.is_float() {
-            return false;
-        }
+/// How much larger than the range of the integers seen an inferred integer type
+/// must be, so that a schema inferred from a few examples doesn't reject
+/// slightly larger numbers.
+///
+/// Headroom only ever widens integer types. If no integer type has enough
+/// headroom, then the widest allowed integer type that contains the integers
+/// seen is used, so more headroom never gives a narrower type.
+///
+/// See [`Hints::with_headroom`][`crate::Hints::with_headroom`] for how this
+/// enum is used.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Headroom {
+    /// Any type that contains the integers seen is used.
+    None,
 
-        let (min, max) = type_.as_range();
-        min <= self.min && max >= self.max
-    }
+    /// The type must contain the integers seen, multiplied by this factor. For
+    /// example, with a factor of 10, integers from 0 to 200 are inferred as
+    /// uint16, because uint8 can't contain 2000.
+    Factor(f64),
+
+    /// The type must be at least one size larger than the narrowest type that
+    /// contains the integers seen. For example, integers from 0 to 200 are
+    /// inferred as uint16 rather than uint8.
+    NextSize,
+
+    /// The type must have at least this many bits, which is 8, 16 or 32. For
+    /// example, with 32 bits, integers from 0 to 200 are inferred as uint32.
+    MinBits(u32),
 }
 
 /// A type of number to infer by default.
 ///
 /// See [`Hints`][`crate::Hints`] for how this enum is used.
-#[derive(Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum NumType {
     /// Corresponds to [`jtd::Type::Int8`].
     Int8,
@@ -89,25 +414,32 @@ pub enum NumType {
 
 impl NumType {
     fn is_float(&self) -> bool {
+        matches!(self, Self::Float32 | Self::Float64)
+    }
+
+    /// Returns the range of integers of this type, or [`None`] for floats.
+    fn as_range(&self) -> Option<(i128, i128)> {
         match self {
-            Self::Float32 | Self::Float64 => true,
-            _ => false,
+            Self::Int8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            Self::Uint8 => Some((u8::MIN as i128, u8::MAX as i128)),
+            Self::Int16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            Self::Uint16 => Some((u16::MIN as i128, u16::MAX as i128)),
+            Self::Int32 => Some((i32::MIN as i128, i32::MAX as i128)),
+            Self::Uint32 => Some((u32::MIN as i128, u32::MAX as i128)),
+            Self::Float32 | Self::Float64 => None,
         }
     }
 
-    fn as_range(&self) -> (f64, f64) {
+    fn bits(&self) -> u32 {
         match self {
-            Self::Int8 => (i8::MIN as f64, i8::MAX as f64),
-            Self::Uint8 => (u8::MIN as f64, u8::MAX as f64),
-            Self::Int16 => (i16::MIN as f64, i16::MAX as f64),
-            Self::Uint16 => (u16::MIN as f64, u16::MAX as f64),
-            Self::Int32 => (i32::MIN as f64, i32::MAX as f64),
-            Self::Uint32 => (u32::MIN as f64, u32::MAX as f64),
-            Self::Float32 | Self::Float64 => (f64::MIN, f64::MAX),
+            Self::Int8 | Self::Uint8 => 8,
+            Self::Int16 | Self::Uint16 => 16,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 32,
+            Self::Float64 => 64,
         }
     }
 
-    fn into_type(&self) -> Type {
+    pub(crate) fn into_type(self) -> Type {
         match self {
             Self::Int8 => Type::Int8,
             Self::Uint8 => Type::Uint8,
@@ -121,6 +453,73 @@ impl NumType {
     }
 }
 
+/// Returns the number of digits before and after the decimal point in a number
+/// written as `notation`, once any exponent is applied. Leading zeros aren't
+/// counted, but trailing ones are, so `012.50` has 2 and 2, and `1.5e1` has 2
+/// and 0.
+fn decimal_digits(notation: &str) -> (u32, u32) {
+    let notation = notation.trim_start_matches('-');
+    let (mantissa, exponent) = match notation.find(&['e', 'E'][..]) {
+        Some(i) => (
+            &notation[..i],
+            notation[i + 1..].parse::<i64>().unwrap_or(0),
+        ),
+        None => (notation, 0),
+    };
+
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = (int_part.len() + frac_part.len()) as i64;
+    let leading_zeros = (int_part.to_owned() + frac_part)
+        .chars()
+        .take_while(|&c| c == '0')
+        .count() as i64;
+
+    let point = int_part.len() as i64 + exponent;
+    let int_digits = (point - leading_zeros).max(0);
+    let scale = (digits - point).max(0);
+
+    (
+        int_digits.min(u32::MAX as i64) as u32,
+        scale.min(u32::MAX as i64) as u32,
+    )
+}
+
+/// Returns whether `f` is the same number after being converted to the nearest
+/// f32, written out in the shortest decimal form that identifies that f32, and
+/// then parsed back.
+///
+/// Comparing the f32 itself to `f` would only accept numbers that are exactly
+/// representable in binary, which excludes most decimals, like 0.1.
+fn round_trips_f32(f: f64) -> bool {
+    let f32_ = f as f32;
+    f32_.is_finite() && f32_.to_string().parse::<f64>() == Ok(f)
+}
+
+/// A 64-bit integer type, which JSON Typedef has no equivalent of.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WideInt {
+    Int64,
+    Uint64,
+}
+
+impl WideInt {
+    /// Returns the `metadata` that tells code generators to use this type.
+    pub fn codegen_metadata(self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Self::Int64 => vec![
+                ("goType", "int64"),
+                ("rustType", "i64"),
+                ("typescriptType", "bigint"),
+            ],
+            Self::Uint64 => vec![
+                ("goType", "uint64"),
+                ("rustType", "u64"),
+                ("typescriptType", "bigint"),
+            ],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,52 +529,346 @@ mod tests {
         let n = InferredNumber::new();
 
         // At first, default always honored.
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Uint16, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Uint32, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        assert_eq!(Type::Uint8, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(Type::Int8, n.into_type(&rules(NumType::Int8)));
+        assert_eq!(Type::Uint16, n.into_type(&rules(NumType::Uint16)));
+        assert_eq!(Type::Int16, n.into_type(&rules(NumType::Int16)));
+        assert_eq!(Type::Uint32, n.into_type(&rules(NumType::Uint32)));
+        assert_eq!(Type::Int32, n.into_type(&rules(NumType::Int32)));
+        assert_eq!(Type::Float32, n.into_type(&rules(NumType::Float32)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Float64)));
 
         // Test expanding to limits of uint8.
         let n = InferredNumber::new()
-            .infer(u8::MIN as f64)
-            .infer(u8::MAX as f64);
-
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Uint8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Uint16, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Uint32, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+            .infer(&u8::MIN.into())
+            .infer(&u8::MAX.into());
+
+        assert_eq!(Type::Uint8, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(Type::Uint8, n.into_type(&rules(NumType::Int8)));
+        assert_eq!(Type::Uint16, n.into_type(&rules(NumType::Uint16)));
+        assert_eq!(Type::Int16, n.into_type(&rules(NumType::Int16)));
+        assert_eq!(Type::Uint32, n.into_type(&rules(NumType::Uint32)));
+        assert_eq!(Type::Int32, n.into_type(&rules(NumType::Int32)));
+        assert_eq!(Type::Float32, n.into_type(&rules(NumType::Float32)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Float64)));
 
         // Test expanding to limits of int8.
         let n = InferredNumber::new()
-            .infer(i8::MIN as f64)
-            .infer(i8::MAX as f64);
-
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Int16, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Int8, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Int32, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+            .infer(&i8::MIN.into())
+            .infer(&i8::MAX.into());
+
+        assert_eq!(Type::Int8, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(Type::Int8, n.into_type(&rules(NumType::Int8)));
+        assert_eq!(Type::Int8, n.into_type(&rules(NumType::Uint16)));
+        assert_eq!(Type::Int16, n.into_type(&rules(NumType::Int16)));
+        assert_eq!(Type::Int8, n.into_type(&rules(NumType::Uint32)));
+        assert_eq!(Type::Int32, n.into_type(&rules(NumType::Int32)));
+        assert_eq!(Type::Float32, n.into_type(&rules(NumType::Float32)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Float64)));
 
         // Test including a non-integer.
-        let n = InferredNumber::new().infer(0.5);
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint8));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int8));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint16));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int16));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Uint32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Int32));
-        assert_eq!(Type::Float32, n.into_type(&NumType::Float32));
-        assert_eq!(Type::Float64, n.into_type(&NumType::Float64));
+        let n = InferredNumber::new().infer(&float(0.5));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Int8)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint16)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Int16)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint32)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Int32)));
+        assert_eq!(Type::Float32, n.into_type(&rules(NumType::Float32)));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Float64)));
+    }
+
+    #[test]
+    fn decimal_digits() {
+        assert_eq!((2, 2), super::decimal_digits("12.99"));
+        assert_eq!((2, 2), super::decimal_digits("-012.50"));
+        assert_eq!((0, 3), super::decimal_digits("0.001"));
+        assert_eq!((0, 3), super::decimal_digits("1e-3"));
+        assert_eq!((2, 0), super::decimal_digits("1.5e1"));
+        assert_eq!((4, 0), super::decimal_digits("1E3"));
+        assert_eq!((0, 0), super::decimal_digits("0"));
+
+        let n = ["12.99", "1040", "0.5"]
+            .iter()
+            .fold(InferredNumber::new(), |n, s| {
+                n.infer(&serde_json::from_str(s).unwrap())
+            });
+        assert_eq!(6, n.precision());
+        assert_eq!(2, n.scale());
+        assert!(n.is_money());
+        assert!(!n.infer(&float(0.125)).is_money());
+    }
+
+    #[test]
+    fn float_notation() {
+        let float_notation_rules = |default| NumTypeRules {
+            float_notation: true,
+            ..rules(default)
+        };
+
+        let n = InferredNumber::new()
+            .infer(&serde_json::from_str("1.0").unwrap())
+            .infer(&serde_json::from_str("3").unwrap());
+        assert_eq!(Type::Uint8, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(
+            Type::Float64,
+            n.into_type(&float_notation_rules(NumType::Uint8))
+        );
+        assert!(!n.is_int_fallback(&float_notation_rules(NumType::Uint8)));
+
+        let n = InferredNumber::new().infer(&serde_json::from_str("2e3").unwrap());
+        assert_eq!(Type::Uint16, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(
+            Type::Float32,
+            n.into_type(&float_notation_rules(NumType::Float32))
+        );
+
+        let n = InferredNumber::new().infer(&serde_json::from_str("42").unwrap());
+        assert_eq!(
+            Type::Uint8,
+            n.into_type(&float_notation_rules(NumType::Uint8))
+        );
+    }
+
+    #[test]
+    fn wide_int() {
+        let n = InferredNumber::new()
+            .infer(&0.into())
+            .infer(&(u32::MAX as u64 + 1).into());
+        assert!(n.is_int_fallback(&rules(NumType::Uint8)));
+        assert!(!n.is_int_fallback(&rules(NumType::Float32)));
+        assert_eq!(Some(WideInt::Int64), n.wide_int());
+
+        let n = n.infer(&i64::MAX.into());
+        assert_eq!(Some(WideInt::Int64), n.wide_int());
+
+        let n = n.infer(&(i64::MAX as u64 + 1).into());
+        assert_eq!(Some(WideInt::Uint64), n.wide_int());
+
+        let n = n.infer(&(-1).into());
+        assert_eq!(None, n.wide_int());
+
+        let n = InferredNumber::new().infer(&float(u32::MAX as f64 + 0.5));
+        assert!(!n.is_int_fallback(&rules(NumType::Uint8)));
+        assert_eq!(None, n.wide_int());
+
+        let n = InferredNumber::new().infer(&u32::MAX.into());
+        assert!(!n.is_int_fallback(&rules(NumType::Uint8)));
+    }
+
+    #[test]
+    fn exact_integers() {
+        // u32::MAX + 1 and i32::MIN - 1 are exactly representable as f64, but
+        // values near u64::MAX are not, and would compare equal to their
+        // neighbours.
+        let n = InferredNumber::new().infer(&u32::MAX.into());
+        assert_eq!(Type::Uint32, n.into_type(&rules(NumType::Uint8)));
+
+        let n = n.infer(&(u32::MAX as u64 + 1).into());
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint8)));
+
+        let n = InferredNumber::new().infer(&(i32::MIN as i64 - 1).into());
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint8)));
+
+        let n = InferredNumber::new().infer(&u64::MAX.into());
+        assert_eq!(Some(WideInt::Uint64), n.wide_int());
+
+        // Whole numbers written with a fraction or exponent are still
+        // integers.
+        let n = InferredNumber::new().infer(&float(1e3));
+        assert_eq!(Type::Uint16, n.into_type(&rules(NumType::Uint8)));
+    }
+
+    #[test]
+    fn float32() {
+        let n = InferredNumber::new()
+            .infer(&float(0.1))
+            .infer(&float(-2.5e10));
+        assert_eq!(Type::Float64, n.into_type(&rules(NumType::Uint8)));
+        assert_eq!(Type::Float32, n.into_type(&float32_rules(NumType::Uint8)));
+
+        // Integers that don't fit in an integer type can be float32 too.
+        let n = n.infer(&5_000_000_000u64.into());
+        assert_eq!(Type::Float32, n.into_type(&float32_rules(NumType::Uint8)));
+
+        let n = n.infer(&float(0.123456789));
+        assert_eq!(Type::Float64, n.into_type(&float32_rules(NumType::Uint8)));
+
+        let n = InferredNumber::new().infer(&float(1e39));
+        assert_eq!(Type::Float64, n.into_type(&float32_rules(NumType::Uint8)));
+    }
+
+    #[test]
+    fn fits() {
+        let n = InferredNumber::new().infer(&float(0.1)).infer(&300.into());
+        assert!(n.fits(&NumType::Float32, &rules(NumType::Uint8)));
+        assert!(n.fits(&NumType::Float64, &rules(NumType::Uint8)));
+        assert!(!n.fits(&NumType::Uint16, &rules(NumType::Uint8)));
+
+        // Whether float32 is chosen by default doesn't matter for a type that's
+        // asked for, but the numbers must still survive a round-trip.
+        let n = n.infer(&float(0.123456789));
+        assert!(!n.fits(&NumType::Float32, &rules(NumType::Uint8)));
+        assert!(!n.fits(&NumType::Float32, &float32_rules(NumType::Uint8)));
+        assert!(n.fits(&NumType::Float64, &rules(NumType::Uint8)));
+
+        let n = InferredNumber::new().infer(&(1u64 << 53).into());
+        assert!(!n.fits(&NumType::Uint32, &rules(NumType::Float64)));
+        assert!(!n.is_inexact_f64());
+        assert!(n.infer(&((1u64 << 53) + 1).into()).is_inexact_f64());
+        assert!(!InferredNumber::new().infer(&float(1e300)).is_inexact_f64());
+    }
+
+    #[test]
+    fn allowed() {
+        let allowed = [NumType::Int32, NumType::Float64];
+        let rules = NumTypeRules {
+            default: NumType::Uint8,
+            allowed: &allowed,
+            float32: true,
+            headroom: Headroom::None,
+            float_notation: false,
+        };
+
+        let n = InferredNumber::new().infer(&0.into()).infer(&200.into());
+        assert_eq!(Type::Int32, n.into_type(&rules));
+
+        let n = n.infer(&float(0.5));
+        assert_eq!(Type::Float64, n.into_type(&rules));
+
+        let n = InferredNumber::new().infer(&u32::MAX.into());
+        assert_eq!(Type::Float64, n.into_type(&rules));
+        assert!(n.is_int_fallback(&rules));
+
+        let allowed = [NumType::Uint8, NumType::Int16];
+        let rules = NumTypeRules {
+            default: NumType::Uint8,
+            allowed: &allowed,
+            float32: false,
+            headroom: Headroom::None,
+            float_notation: false,
+        };
+
+        assert_eq!(Type::Int16, n.into_type(&rules));
+        assert!(!n.fits_allowed(&rules));
+        assert!(!n.is_int_fallback(&rules));
+    }
+
+    #[test]
+    fn more_headroom_is_never_narrower() {
+        let factors = [1.0, 1.5, 10.0, 1000.0, 1e5, 1e8, 1e12];
+        let bits = [8, 16, 32];
+
+        for value in [0i64, 200, -100, 70_000, -70_000, 3_000_000_000] {
+            let n = InferredNumber::new().infer(&value.into());
+            let width = |headroom| {
+                n.into_type(&NumTypeRules {
+                    headroom,
+                    ..rules(NumType::Uint8)
+                })
+            };
+            let bits_of = |type_: Type| match type_ {
+                Type::Int8 | Type::Uint8 => 8,
+                Type::Int16 | Type::Uint16 => 16,
+                Type::Int32 | Type::Uint32 => 32,
+                _ => 64,
+            };
+
+            let widths: Vec<_> = factors
+                .iter()
+                .map(|factor| bits_of(width(Headroom::Factor(*factor))))
+                .collect();
+            assert!(
+                widths.windows(2).all(|w| w[0] <= w[1]),
+                "{}: {:?}",
+                value,
+                widths
+            );
+
+            let widths: Vec<_> = bits
+                .iter()
+                .map(|bits| bits_of(width(Headroom::MinBits(*bits))))
+                .collect();
+            assert!(
+                widths.windows(2).all(|w| w[0] <= w[1]),
+                "{}: {:?}",
+                value,
+                widths
+            );
+        }
+    }
+
+    #[test]
+    fn headroom() {
+        let n = InferredNumber::new().infer(&0.into()).infer(&200.into());
+        let headroom_rules = |headroom| NumTypeRules {
+            headroom,
+            ..rules(NumType::Uint8)
+        };
+
+        assert_eq!(Type::Uint8, n.into_type(&headroom_rules(Headroom::None)));
+        assert_eq!(
+            Type::Uint8,
+            n.into_type(&headroom_rules(Headroom::Factor(1.2)))
+        );
+        assert_eq!(
+            Type::Uint16,
+            n.into_type(&headroom_rules(Headroom::Factor(1.5)))
+        );
+        assert_eq!(
+            Type::Uint16,
+            n.into_type(&headroom_rules(Headroom::NextSize))
+        );
+        assert_eq!(
+            Type::Uint32,
+            n.into_type(&headroom_rules(Headroom::MinBits(32)))
+        );
+
+        let n = n.infer(&(-100).into());
+        assert_eq!(
+            Type::Int32,
+            n.into_type(&headroom_rules(Headroom::Factor(200.0)))
+        );
+
+        // More headroom than any integer type has gives the widest one.
+        assert_eq!(
+            Type::Int32,
+            n.into_type(&headroom_rules(Headroom::Factor(1e8)))
+        );
+
+        // Headroom never turns integers into floats.
+        let n = InferredNumber::new().infer(&u32::MAX.into());
+        assert_eq!(
+            Type::Uint32,
+            n.into_type(&headroom_rules(Headroom::NextSize))
+        );
+
+        // Floats are unaffected.
+        let n = InferredNumber::new().infer(&float(0.5));
+        assert_eq!(
+            Type::Float64,
+            n.into_type(&headroom_rules(Headroom::NextSize))
+        );
+    }
+
+    fn rules(default: NumType) -> NumTypeRules<'static> {
+        NumTypeRules {
+            default,
+            allowed: &[],
+            float32: false,
+            headroom: Headroom::None,
+            float_notation: false,
+        }
+    }
+
+    fn float32_rules(default: NumType) -> NumTypeRules<'static> {
+        NumTypeRules {
+            float32: true,
+            ..rules(default)
+        }
+    }
+
+    fn float(f: f64) -> Number {
+        Number::from_f64(f).unwrap()
     }
 }
@@ -1,8 +1,10 @@
-use crate::hints::Hints;
-use crate::inferred_number::InferredNumber;
+use crate::extended_json::{self, EncodedNumber, Unwrapped};
+use crate::hints::{Hints, TypeHint, WILDCARD};
+use crate::inferred_number::{InferredNumber, NumType};
+use crate::stats::Stats;
 use chrono::DateTime;
 use jtd::{Schema, Type};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Debug)]
@@ -12,12 +14,20 @@ pub enum InferredSchema {
     Boolean,
     Number(InferredNumber),
     String,
-    Timestamp,
+    EncodedNumber(EncodedNumber),
+    JsonString(Box<InferredSchema>),
+    /// Strings that are all timestamps, and how many of them there were.
+    Timestamp(usize),
     Enum(BTreeSet<String>),
     Array(Box<InferredSchema>),
     Properties {
         required: BTreeMap<String, InferredSchema>,
         optional: BTreeMap<String, InferredSchema>,
+
+        /// How many examples there were, and which of them each property
+        /// hinted as required was missing from.
+        examples: usize,
+        omissions: BTreeMap<String, Omissions>,
     },
     Values(Box<InferredSchema>),
     Discriminator {
@@ -25,11 +35,56 @@ pub enum InferredSchema {
         mapping: BTreeMap<String, InferredSchema>,
     },
     Nullable(Box<InferredSchema>),
+    WithStats(Box<InferredSchema>, Stats),
+    Hinted {
+        type_: TypeHint,
+        contradictions: usize,
+        example: Option<Value>,
+    },
+}
+
+/// Which examples a property was missing from.
+#[derive(Debug)]
+pub struct Omissions {
+    count: usize,
+
+    /// The index of the first of them.
+    first: usize,
 }
 
 impl InferredSchema {
     pub fn infer(self, value: Value, hints: &Hints) -> Self {
+        // Statistics wrap the rest of the inference for a part of the input,
+        // so that they see every example of it, including nulls.
+        let inferred = match self {
+            InferredSchema::WithStats(..) => self,
+            _ if hints.stats_key().is_some() => {
+                InferredSchema::WithStats(Box::new(self), Stats::default())
+            }
+            _ => self,
+        };
+
+        let value = if hints.is_extended_json() {
+            match extended_json::unwrap(value) {
+                Unwrapped::Value(value) => value,
+                Unwrapped::EncodedNumber(encoded, text) => {
+                    return inferred.infer_encoded_number(encoded, text, hints)
+                }
+            }
+        } else {
+            value
+        };
+
+        inferred.infer_value(value, hints)
+    }
+
+    fn infer_value(self, value: Value, hints: &Hints) -> Self {
         match (self, value) {
+            (InferredSchema::WithStats(sub_infer, stats), value) => {
+                let stats = stats.record(&value, hints.max_samples());
+                InferredSchema::WithStats(Box::new(sub_infer.infer_value(value, hints)), stats)
+            }
+
             // Handle all null-related cases first. After these two branches,
             // neither the current inference nor the incoming data will be null.
             //
@@ -39,9 +94,54 @@ impl InferredSchema {
             // If this proves to be a performance concern, we may want to check
             // if the sub-inference is Nullable, and avoid wrapping in that
             // case.
-            (sub_infer @ _, Value::Null) => InferredSchema::Nullable(Box::new(sub_infer)),
-            (InferredSchema::Nullable(sub_infer), value @ _) => {
-                InferredSchema::Nullable(Box::new(sub_infer.infer(value, hints)))
+            //
+            // A type hint still applies if the first example is null, so that
+            // nulls only make the hinted type nullable, even if every example
+            // is null.
+            (InferredSchema::Unknown, Value::Null) if hints.type_hint().is_some() => {
+                InferredSchema::Nullable(Box::new(InferredSchema::Hinted {
+                    type_: hints.type_hint().unwrap(),
+                    contradictions: 0,
+                    example: None,
+                }))
+            }
+            (sub_infer, Value::Null) => InferredSchema::Nullable(Box::new(sub_infer)),
+            (InferredSchema::Nullable(sub_infer), value) => {
+                InferredSchema::Nullable(Box::new(sub_infer.infer_value(value, hints)))
+            }
+
+            // Handle parts of the input whose type is given by a hint. Examples
+            // of other types are counted, so they can be reported, rather than
+            // widening the type.
+            (InferredSchema::Unknown, value) if hints.type_hint().is_some() => {
+                InferredSchema::Hinted {
+                    type_: hints.type_hint().unwrap(),
+                    contradictions: 0,
+                    example: None,
+                }
+                .infer_value(value, hints)
+            }
+            (
+                InferredSchema::Hinted {
+                    type_,
+                    contradictions,
+                    example,
+                },
+                value,
+            ) => {
+                if is_type_hint_example(&type_, &value, hints) {
+                    InferredSchema::Hinted {
+                        type_,
+                        contradictions,
+                        example,
+                    }
+                } else {
+                    InferredSchema::Hinted {
+                        type_,
+                        contradictions: contradictions + 1,
+                        example: example.or(Some(value)),
+                    }
+                }
             }
 
             // Handle all cases related to when we don't have a prior on what
@@ -51,7 +151,7 @@ impl InferredSchema {
             // particular form.
             (InferredSchema::Unknown, Value::Bool(_)) => InferredSchema::Boolean,
             (InferredSchema::Unknown, Value::Number(n)) => {
-                InferredSchema::Number(InferredNumber::new().infer(n.as_f64().unwrap()))
+                InferredSchema::Number(InferredNumber::new().infer(&n))
             }
             (InferredSchema::Unknown, Value::String(s)) => {
                 if hints.is_enum_active() {
@@ -59,25 +159,33 @@ impl InferredSchema {
                     values.insert(s);
 
                     InferredSchema::Enum(values)
-                } else if DateTime::parse_from_rfc3339(&s).is_ok() {
-                    InferredSchema::Timestamp
+                } else if let Some(value) = parse_json_string(&s, hints) {
+                    InferredSchema::JsonString(Box::new(
+                        InferredSchema::Unknown.infer(value, hints),
+                    ))
+                } else if hints.is_timestamp_active() && DateTime::parse_from_rfc3339(&s).is_ok() {
+                    InferredSchema::Timestamp(1)
                 } else {
                     InferredSchema::String
                 }
             }
             (InferredSchema::Unknown, Value::Array(vals)) => {
+                // Elements share a schema, so hints for any index apply to all
+                // of them, as they do when the schema is output.
+                let element_hints = hints.element_hints();
                 let mut sub_infer = InferredSchema::Unknown;
-                for (i, v) in vals.into_iter().enumerate() {
-                    sub_infer = sub_infer.infer(v, &hints.sub_hints(&i.to_string()));
+                for v in vals {
+                    sub_infer = sub_infer.infer(v, &element_hints);
                 }
 
                 InferredSchema::Array(Box::new(sub_infer))
             }
             (InferredSchema::Unknown, Value::Object(mut obj)) => {
                 if hints.is_values_active() {
+                    let value_hints = hints.value_hints();
                     let mut sub_infer = InferredSchema::Unknown;
-                    for (k, v) in obj {
-                        sub_infer = sub_infer.infer(v, &hints.sub_hints(&k));
+                    for v in obj.into_iter().map(|(_, v)| v) {
+                        sub_infer = sub_infer.infer(v, &value_hints);
                     }
 
                     return InferredSchema::Values(Box::new(sub_infer));
@@ -106,6 +214,8 @@ impl InferredSchema {
                 InferredSchema::Properties {
                     required: props,
                     optional: BTreeMap::new(),
+                    examples: 1,
+                    omissions: BTreeMap::new(),
                 }
             }
 
@@ -120,24 +230,40 @@ impl InferredSchema {
 
             // Handle updating an inferred number primitive.
             (InferredSchema::Number(inferred_number), Value::Number(n)) => {
-                InferredSchema::Number(inferred_number.infer(n.as_f64().unwrap()))
+                InferredSchema::Number(inferred_number.infer(&n))
             }
             (InferredSchema::Number(_), _) => InferredSchema::Any,
 
             // Handle updating an inferred timestamp primitive.
-            (InferredSchema::Timestamp, Value::String(s)) => {
+            (InferredSchema::Timestamp(count), Value::String(s)) => {
                 if DateTime::parse_from_rfc3339(&s).is_ok() {
-                    InferredSchema::Timestamp
+                    InferredSchema::Timestamp(count + 1)
                 } else {
                     InferredSchema::String
                 }
             }
-            (InferredSchema::Timestamp, _) => InferredSchema::Any,
+            (InferredSchema::Timestamp(_), _) => InferredSchema::Any,
 
             // Handle updating an inferred string primitive.
             (InferredSchema::String, Value::String(_)) => InferredSchema::String,
             (InferredSchema::String, _) => InferredSchema::Any,
 
+            // Handle updating an inferred Extended JSON number. Ordinary
+            // strings are still strings, but we can no longer say what they
+            // encode.
+            (InferredSchema::EncodedNumber(_), Value::String(_)) => InferredSchema::String,
+            (InferredSchema::EncodedNumber(_), _) => InferredSchema::Any,
+
+            // Handle updating an inferred JSON-encoded string. If any string
+            // doesn't contain JSON, then it's just an ordinary string.
+            (InferredSchema::JsonString(prior), Value::String(s)) => {
+                match parse_json_string(&s, hints) {
+                    Some(value) => InferredSchema::JsonString(Box::new(prior.infer(value, hints))),
+                    None => InferredSchema::String,
+                }
+            }
+            (InferredSchema::JsonString(_), _) => InferredSchema::Any,
+
             // Handle updating an inferred enum.
             (InferredSchema::Enum(mut values), Value::String(s)) => {
                 values.insert(s);
@@ -147,9 +273,10 @@ impl InferredSchema {
 
             // Handle updating an inferred array.
             (InferredSchema::Array(prior), Value::Array(vals)) => {
+                let element_hints = hints.element_hints();
                 let mut sub_infer = *prior;
-                for (i, v) in vals.into_iter().enumerate() {
-                    sub_infer = sub_infer.infer(v, &hints.sub_hints(&i.to_string()));
+                for v in vals {
+                    sub_infer = sub_infer.infer(v, &element_hints);
                 }
 
                 InferredSchema::Array(Box::new(sub_infer))
@@ -161,12 +288,45 @@ impl InferredSchema {
                 InferredSchema::Properties {
                     mut required,
                     mut optional,
+                    examples,
+                    mut omissions,
                 },
                 Value::Object(map),
             ) => {
+                // Omissions are only needed to warn about properties hinted
+                // as required, so the others aren't counted.
+                for k in required.keys().chain(optional.keys()) {
+                    if !map.contains_key(k) && hints.keeps_required(k) {
+                        omissions
+                            .entry(k.clone())
+                            .or_insert(Omissions {
+                                count: 0,
+                                first: examples,
+                            })
+                            .count += 1;
+                    }
+                }
+
+                // Properties seen for the first time were missing from every
+                // example before this one.
+                for k in map.keys() {
+                    if !required.contains_key(k)
+                        && !optional.contains_key(k)
+                        && hints.keeps_required(k)
+                    {
+                        omissions.insert(
+                            k.clone(),
+                            Omissions {
+                                count: examples,
+                                first: 0,
+                            },
+                        );
+                    }
+                }
+
                 let missing_required_keys: Vec<_> = required
                     .keys()
-                    .filter(|k| !map.contains_key(k.clone()))
+                    .filter(|k| !map.contains_key(*k))
                     .cloned()
                     .collect();
 
@@ -188,18 +348,24 @@ impl InferredSchema {
                     }
                 }
 
-                InferredSchema::Properties { required, optional }
+                InferredSchema::Properties {
+                    required,
+                    optional,
+                    examples: examples + 1,
+                    omissions,
+                }
             }
             (InferredSchema::Properties { .. }, _) => InferredSchema::Any,
 
             // Handle updating an inferred values form.
             (InferredSchema::Values(prior), Value::Object(map)) => {
                 let mut sub_infer = *prior;
-                for (k, v) in map {
-                    sub_infer = InferredSchema::Unknown.infer(v, &hints.sub_hints(&k));
+                let value_hints = hints.value_hints();
+                for v in map.into_iter().map(|(_, v)| v) {
+                    sub_infer = InferredSchema::Unknown.infer(v, &value_hints);
                 }
 
-                return InferredSchema::Values(Box::new(sub_infer));
+                InferredSchema::Values(Box::new(sub_infer))
             }
             (InferredSchema::Values(_), _) => InferredSchema::Any,
 
@@ -235,8 +401,202 @@ impl InferredSchema {
         }
     }
 
+    /// Infers from a number that Extended JSON encodes as `text`.
+    fn infer_encoded_number(self, encoded: EncodedNumber, text: String, hints: &Hints) -> Self {
+        match self {
+            InferredSchema::Unknown if hints.type_hint().is_some() => InferredSchema::Hinted {
+                type_: hints.type_hint().unwrap(),
+                contradictions: 0,
+                example: None,
+            }
+            .infer_encoded_number(encoded, text, hints),
+            InferredSchema::Unknown => InferredSchema::EncodedNumber(encoded),
+            InferredSchema::EncodedNumber(prior) => {
+                InferredSchema::EncodedNumber(prior.max(encoded))
+            }
+            InferredSchema::Nullable(sub_infer) => InferredSchema::Nullable(Box::new(
+                sub_infer.infer_encoded_number(encoded, text, hints),
+            )),
+
+            // Encoded numbers are strings on the wire, so that's what
+            // statistics and examples show.
+            InferredSchema::WithStats(sub_infer, stats) => {
+                let stats = stats.record(&Value::String(text.clone()), hints.max_samples());
+                InferredSchema::WithStats(
+                    Box::new(sub_infer.infer_encoded_number(encoded, text, hints)),
+                    stats,
+                )
+            }
+
+            // Encoded numbers are strings on the wire, so they only contradict
+            // hints of other types.
+            InferredSchema::Hinted {
+                type_: type_ @ (TypeHint::String | TypeHint::Any),
+                contradictions,
+                example,
+            } => InferredSchema::Hinted {
+                type_,
+                contradictions,
+                example,
+            },
+            InferredSchema::Hinted {
+                type_,
+                contradictions,
+                example,
+            } => InferredSchema::Hinted {
+                type_,
+                contradictions: contradictions + 1,
+                example: example.or(Some(Value::String(text))),
+            },
+
+            // Encoded numbers are strings on the wire, but not ones that are
+            // timestamps.
+            InferredSchema::String | InferredSchema::Timestamp(_) => InferredSchema::String,
+            _ => InferredSchema::Any,
+        }
+    }
+
     pub fn into_schema(self, hints: &Hints) -> Schema {
+        let inferred = if hints.is_nullable_active() {
+            InferredSchema::Nullable(Box::new(self))
+        } else {
+            self
+        };
+
+        let mut schema = inferred.into_bare_schema(hints);
+        if let Some(description) = hints.description() {
+            metadata_mut(&mut schema).insert(
+                "description".to_owned(),
+                Value::String(description.to_owned()),
+            );
+        }
+
+        schema
+    }
+
+    /// Adds warnings about parts of the inference that the schema can't
+    /// represent well to `warnings`. `path` is a JSON Pointer to this part of
+    /// the input.
+    pub fn warnings(&self, hints: &Hints, path: &str, warnings: &mut Vec<String>) {
         match self {
+            InferredSchema::Number(inferred_number) => {
+                let rules = hints.num_type_rules();
+                let type_ = inferred_number.into_type(&rules);
+
+                if !inferred_number.fits_allowed(&rules) {
+                    warnings.push(format!(
+                        "{}: numbers don't fit in any of the allowed number types, so they were inferred as {}, which doesn't accept all of them",
+                        display_path(path),
+                        type_name(&type_)
+                    ));
+                } else if inferred_number.is_int_fallback(&rules) {
+                    warnings.push(format!(
+                        "{}: integers are too large for any allowed integer type, so they were inferred as {}, which can't precisely represent all integers that large; consider marking them as 64-bit integers, or representing them as strings",
+                        display_path(path),
+                        type_name(&type_)
+                    ));
+                } else if hints.is_decimal_metadata()
+                    && inferred_number.is_money()
+                    && matches!(type_, Type::Float32 | Type::Float64)
+                {
+                    warnings.push(format!(
+                        "{}: numbers look like amounts of money, with up to 2 decimal places, but {} can't represent most of those exactly; consider representing them as decimal strings",
+                        display_path(path),
+                        type_name(&type_)
+                    ));
+                }
+            }
+            InferredSchema::Hinted {
+                type_,
+                contradictions,
+                example,
+            } if *contradictions > 0 => {
+                let (count, verb) = if *contradictions == 1 {
+                    ("1 example".to_owned(), "contradicts")
+                } else {
+                    (format!("{} examples", contradictions), "contradict")
+                };
+
+                let such_as = match example {
+                    Some(example) => format!(", such as {}", truncate_example(example)),
+                    None => String::new(),
+                };
+
+                warnings.push(format!(
+                    "{}: {} {} the type hint {}{}",
+                    display_path(path),
+                    count,
+                    verb,
+                    type_hint_name(type_),
+                    such_as
+                ));
+            }
+            InferredSchema::JsonString(sub_infer)
+            | InferredSchema::Nullable(sub_infer)
+            | InferredSchema::WithStats(sub_infer, _) => sub_infer.warnings(hints, path, warnings),
+            InferredSchema::Array(sub_infer) => sub_infer.warnings(
+                &hints.element_hints(),
+                &format!("{}/{}", path, WILDCARD),
+                warnings,
+            ),
+            InferredSchema::Values(sub_infer) => sub_infer.warnings(
+                &hints.value_hints(),
+                &format!("{}/{}", path, WILDCARD),
+                warnings,
+            ),
+            InferredSchema::Properties {
+                required,
+                optional,
+                examples,
+                omissions,
+            } => {
+                for (k, sub_infer) in required.iter().chain(optional) {
+                    let sub_hints = hints.sub_hints(k);
+                    let sub_path = format!("{}/{}", path, k.replace('~', "~0").replace('/', "~1"));
+
+                    if let Some(omitted) = omissions.get(k) {
+                        warnings.push(format!(
+                            "{}: required property is missing from {} of {} examples, starting with example {}",
+                            sub_path,
+                            omitted.count,
+                            examples,
+                            omitted.first + 1
+                        ));
+                    }
+
+                    sub_infer.warnings(&sub_hints, &sub_path, warnings);
+                }
+            }
+            InferredSchema::Discriminator { mapping, .. } => {
+                for sub_infer in mapping.values() {
+                    sub_infer.warnings(hints, path, warnings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts the inference to a schema, without the metadata that comes
+    /// from the hints for its path.
+    ///
+    /// Some inferences wrap another one for the same part of the input, and
+    /// use this to convert the inference they wrap.
+    fn into_bare_schema(self, hints: &Hints) -> Schema {
+        match self {
+            InferredSchema::WithStats(sub_infer, stats) => {
+                let mut schema = sub_infer.into_bare_schema(hints);
+                if let Some(key) = hints.stats_key() {
+                    let stats = if hints.is_redacted() {
+                        stats.redacted()
+                    } else {
+                        stats
+                    };
+
+                    metadata_mut(&mut schema).insert(key.to_owned(), stats.into_value());
+                }
+
+                schema
+            }
             InferredSchema::Unknown | InferredSchema::Any => Schema::Empty {
                 definitions: Default::default(),
                 metadata: Default::default(),
@@ -247,24 +607,101 @@ impl InferredSchema {
                 nullable: false,
                 type_: Type::Boolean,
             },
-            InferredSchema::Number(inferred_number) => Schema::Type {
+            InferredSchema::Number(inferred_number) => {
+                let rules = hints.num_type_rules();
+                let wide_int = inferred_number.wide_int().filter(|_| {
+                    hints.is_int64_metadata() && inferred_number.is_int_fallback(&rules)
+                });
+
+                let mut metadata: BTreeMap<_, _> = wide_int
+                    .map(|wide_int| {
+                        wide_int
+                            .codegen_metadata()
+                            .into_iter()
+                            .map(|(k, v)| (k.to_owned(), Value::String(v.to_owned())))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                if hints.is_decimal_metadata() {
+                    metadata.insert(
+                        "decimal".to_owned(),
+                        json!({
+                            "precision": inferred_number.precision(),
+                            "scale": inferred_number.scale(),
+                        }),
+                    );
+                }
+
+                Schema::Type {
+                    definitions: Default::default(),
+                    metadata,
+                    nullable: false,
+                    type_: inferred_number.into_type(&rules),
+                }
+            }
+            InferredSchema::String => Schema::Type {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                type_: inferred_number.into_type(hints.default_num_type()),
+                type_: Type::String,
             },
-            InferredSchema::String => Schema::Type {
+            InferredSchema::EncodedNumber(encoded) => Schema::Type {
                 definitions: Default::default(),
-                metadata: Default::default(),
+                metadata: vec![(
+                    "extendedJsonType".to_owned(),
+                    Value::String(encoded.as_str().to_owned()),
+                )]
+                .into_iter()
+                .collect(),
                 nullable: false,
                 type_: Type::String,
             },
-            InferredSchema::Timestamp => Schema::Type {
+            InferredSchema::JsonString(sub_infer) => Schema::Type {
+                definitions: Default::default(),
+                metadata: vec![(
+                    "encodedJson".to_owned(),
+                    serde_json::to_value(sub_infer.into_bare_schema(hints).into_serde_schema())
+                        .expect("serialize encoded json schema"),
+                )]
+                .into_iter()
+                .collect(),
+                nullable: false,
+                type_: Type::String,
+            },
+            InferredSchema::Timestamp(count) => Schema::Type {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                type_: Type::Timestamp,
+                // Too few timestamps may just be strings that happen to look
+                // like timestamps.
+                type_: if count < hints.min_timestamp_samples() {
+                    Type::String
+                } else {
+                    Type::Timestamp
+                },
             },
+            InferredSchema::Hinted { type_, .. } => {
+                let type_ = match type_ {
+                    TypeHint::String => Type::String,
+                    TypeHint::Timestamp => Type::Timestamp,
+                    TypeHint::Boolean => Type::Boolean,
+                    TypeHint::Number(num_type) => num_type.into_type(),
+                    TypeHint::Any => {
+                        return Schema::Empty {
+                            definitions: Default::default(),
+                            metadata: Default::default(),
+                        }
+                    }
+                };
+
+                Schema::Type {
+                    definitions: Default::default(),
+                    metadata: Default::default(),
+                    nullable: false,
+                    type_,
+                }
+            }
             InferredSchema::Enum(values) => Schema::Enum {
                 definitions: Default::default(),
                 metadata: Default::default(),
@@ -275,23 +712,43 @@ impl InferredSchema {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                elements: Box::new(sub_infer.into_schema(hints)),
+                elements: Box::new(sub_infer.into_schema(&hints.element_hints())),
             },
-            InferredSchema::Properties { required, optional } => {
-                let properties_is_present = !required.is_empty();
+            InferredSchema::Properties {
+                required, optional, ..
+            } => {
+                let mut properties = BTreeMap::new();
+                let mut optional_properties = BTreeMap::new();
+
+                let all = required
+                    .into_iter()
+                    .map(|(k, v)| (k, v, true))
+                    .chain(optional.into_iter().map(|(k, v)| (k, v, false)));
+
+                for (k, v, is_required) in all {
+                    let sub_hints = hints.sub_hints(&k);
+
+                    // Hints say whether a property is required, regardless of
+                    // whether every example had it.
+                    let is_required = !sub_hints.is_optional_active()
+                        && (is_required || sub_hints.is_required_active());
+
+                    let sub_schema = v.into_schema(&sub_hints);
+                    if is_required {
+                        properties.insert(k, sub_schema);
+                    } else {
+                        optional_properties.insert(k, sub_schema);
+                    }
+                }
+
+                let properties_is_present = !properties.is_empty();
 
                 Schema::Properties {
                     definitions: Default::default(),
                     metadata: Default::default(),
                     nullable: false,
-                    properties: required
-                        .into_iter()
-                        .map(|(k, v)| (k, v.into_schema(hints)))
-                        .collect(),
-                    optional_properties: optional
-                        .into_iter()
-                        .map(|(k, v)| (k, v.into_schema(hints)))
-                        .collect(),
+                    properties,
+                    optional_properties,
                     properties_is_present,
                     additional_properties: false,
                 }
@@ -300,7 +757,7 @@ impl InferredSchema {
                 definitions: Default::default(),
                 metadata: Default::default(),
                 nullable: false,
-                values: Box::new(sub_infer.into_schema(hints)),
+                values: Box::new(sub_infer.into_schema(&hints.value_hints())),
             },
             InferredSchema::Discriminator {
                 discriminator,
@@ -312,10 +769,10 @@ impl InferredSchema {
                 discriminator,
                 mapping: mapping
                     .into_iter()
-                    .map(|(k, v)| (k, v.into_schema(hints)))
+                    .map(|(k, v)| (k, v.into_bare_schema(hints)))
                     .collect(),
             },
-            InferredSchema::Nullable(sub_infer) => match sub_infer.into_schema(hints) {
+            InferredSchema::Nullable(sub_infer) => match sub_infer.into_bare_schema(hints) {
                 Schema::Ref { .. } => unreachable!("ref form inferred"),
 
                 s @ Schema::Empty { .. } => s,
@@ -397,3 +854,98 @@ impl InferredSchema {
         }
     }
 }
+
+/// Parses a string as JSON, if hints say to look for JSON-encoded strings and
+/// the string contains a JSON object or array.
+fn parse_json_string(s: &str, hints: &Hints) -> Option<Value> {
+    if !hints.is_json_string_active() {
+        return None;
+    }
+
+    // Avoid attempting to parse strings that can't possibly be an object or
+    // array.
+    if !s.trim_start().starts_with(['{', '[']) {
+        return None;
+    }
+
+    serde_json::from_str(s).ok()
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "the root"
+    } else {
+        path
+    }
+}
+
+/// Returns whether `value` is an example of the type given by a hint.
+fn is_type_hint_example(type_: &TypeHint, value: &Value, hints: &Hints) -> bool {
+    match (type_, value) {
+        (TypeHint::Any, _) | (TypeHint::String, Value::String(_)) => true,
+        (TypeHint::Timestamp, Value::String(s)) => DateTime::parse_from_rfc3339(s).is_ok(),
+        (TypeHint::Boolean, Value::Bool(_)) => true,
+        (TypeHint::Number(num_type), Value::Number(n)) => {
+            let number = InferredNumber::new().infer(n);
+
+            // With --int64-metadata, integers that a float64 can't hold exactly
+            // are 64-bit integers, not floats.
+            let is_int64 = hints.is_int64_metadata()
+                && *num_type == NumType::Float64
+                && number.wide_int().is_some()
+                && number.is_inexact_f64();
+
+            number.fits(num_type, &hints.num_type_rules()) && !is_int64
+        }
+        _ => false,
+    }
+}
+
+/// Returns an example as JSON, cut short if it's long.
+fn truncate_example(example: &Value) -> String {
+    let example = example.to_string();
+    if example.chars().count() > 100 {
+        format!("{}...", example.chars().take(100).collect::<String>())
+    } else {
+        example
+    }
+}
+
+fn type_hint_name(type_: &TypeHint) -> &'static str {
+    match type_ {
+        TypeHint::String => "string",
+        TypeHint::Timestamp => "timestamp",
+        TypeHint::Boolean => "boolean",
+        TypeHint::Number(num_type) => type_name(&num_type.into_type()),
+        TypeHint::Any => "any",
+    }
+}
+
+fn type_name(type_: &Type) -> &'static str {
+    match type_ {
+        Type::Boolean => "boolean",
+        Type::Float32 => "float32",
+        Type::Float64 => "float64",
+        Type::Int8 => "int8",
+        Type::Uint8 => "uint8",
+        Type::Int16 => "int16",
+        Type::Uint16 => "uint16",
+        Type::Int32 => "int32",
+        Type::Uint32 => "uint32",
+        Type::String => "string",
+        Type::Timestamp => "timestamp",
+    }
+}
+
+fn metadata_mut(schema: &mut Schema) -> &mut BTreeMap<String, Value> {
+    match schema {
+        Schema::Empty { metadata, .. }
+        | Schema::Ref { metadata, .. }
+        | Schema::Type { metadata, .. }
+        | Schema::Enum { metadata, .. }
+        | Schema::Elements { metadata, .. }
+        | Schema::Properties { metadata, .. }
+        | Schema::Values { metadata, .. }
+        | Schema::Discriminator { metadata, .. } => metadata,
+    }
+}
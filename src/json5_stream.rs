@@ -0,0 +1,129 @@
+/// Splits a sequence of JSON5 documents into the text of each document.
+///
+/// Like the default JSON input, documents can be separated by whitespace (or
+/// comments), or simply follow one another, as in `{}{}`. The text of each
+/// document can be parsed with [`json5::from_str`]. Nothing is validated here,
+/// so an invalid document is only reported when it's parsed; if a document
+/// isn't finished by the end of the input, all of the rest of the input is
+/// returned as the last document.
+///
+/// ```
+/// use jtd_infer::split_json5;
+///
+/// assert_eq!(
+///     vec!["{ a: 1, }", "// a comment\n[2]", "'three'"],
+///     split_json5("{ a: 1, }\n// a comment\n[2] 'three'\n"),
+/// );
+/// ```
+pub fn split_json5(input: &str) -> Vec<&str> {
+    let mut documents = Vec::new();
+
+    // Where the current document starts, including any comments and
+    // whitespace before it.
+    let mut start = 0;
+    // Whether the current document has any content besides comments yet.
+    let mut started = false;
+    // Whether the current document is a scalar, like a number or `true`, which
+    // ends at the first character that can't be part of it.
+    let mut scalar = false;
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if let Some(q) = quote {
+            if c == '\\' {
+                chars.next();
+            } else if c == q {
+                quote = None;
+                if depth == 0 {
+                    documents.push(input[start..i + c.len_utf8()].trim_start());
+                    start = i + c.len_utf8();
+                    started = false;
+                }
+            }
+
+            continue;
+        }
+
+        let comment = c == '/' && matches!(chars.peek(), Some((_, '/')) | Some((_, '*')));
+        let ends_scalar = comment || c.is_whitespace() || "{[\"'".contains(c);
+
+        if scalar && ends_scalar {
+            documents.push(input[start..i].trim_start());
+            start = i;
+            started = false;
+            scalar = false;
+        }
+
+        if comment {
+            if let Some((_, '/')) = chars.next() {
+                while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+            } else {
+                let mut prev = None;
+                for (_, c) in chars.by_ref() {
+                    if prev == Some('*') && c == '/' {
+                        break;
+                    }
+
+                    prev = Some(c);
+                }
+            }
+
+            continue;
+        }
+
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if !started && depth == 0 {
+            started = true;
+            scalar = !"{[\"'".contains(c);
+        }
+
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    documents.push(input[start..i + 1].trim_start());
+                    start = i + 1;
+                    started = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if started {
+        documents.push(input[start..].trim());
+    }
+
+    documents
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn split_json5() {
+        assert_eq!(Vec::<&str>::new(), super::split_json5(" \n// nothing\n"));
+        assert_eq!(vec!["{}", "{}", "[]"], super::split_json5("{}{}\n[]"));
+        assert_eq!(
+            vec!["1", "-2.5e3", "true", "null", "Infinity"],
+            super::split_json5("1 -2.5e3\ntrue\tnull Infinity")
+        );
+        assert_eq!(vec!["1", "[2]"], super::split_json5("1[2]"));
+        assert_eq!(vec!["1", "// c\n2"], super::split_json5("1// c\n2"));
+        assert_eq!(
+            vec![r#"{ a: "}", b: '\'{' }"#, "/* ] */ [']']"],
+            super::split_json5(r#"{ a: "}", b: '\'{' } /* ] */ [']']"#)
+        );
+        assert_eq!(
+            vec![r#""a \" b""#, "'c'"],
+            super::split_json5(r#""a \" b"'c'"#)
+        );
+        assert_eq!(vec!["{ a: [1, 2"], super::split_json5("{ a: [1, 2 \n"));
+    }
+}
@@ -0,0 +1,305 @@
+use jtd::{Schema, Type};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+const DEFS: &str = "#/$defs/";
+
+/// Converts a JSON Typedef schema into an equivalent JSON Schema (draft
+/// 2020-12) document.
+///
+/// Each JSON Typedef form is converted to the JSON Schema keywords that accept
+/// the same inputs. Integer types become `integer` with a `minimum` and
+/// `maximum`, the properties form becomes an object with `required` properties
+/// and `additionalProperties` set to `false`, the discriminator form becomes a
+/// `oneOf` whose branches use `const` on the discriminator property, and so
+/// on. `definitions` become `$defs`, and the `description` in a schema's
+/// `metadata` is kept.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_json_schema;
+///
+/// let schema = json!({
+///     "properties": { "age": { "type": "uint8", "nullable": true }},
+///     "optionalProperties": { "name": { "type": "string" }},
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     json!({
+///         "$schema": "https://json-schema.org/draft/2020-12/schema",
+///         "type": "object",
+///         "properties": {
+///             "age": { "type": ["integer", "null"], "minimum": 0, "maximum": 255 },
+///             "name": { "type": "string" },
+///         },
+///         "required": ["age"],
+///         "additionalProperties": false,
+///     }),
+///     to_json_schema(&schema),
+/// );
+/// ```
+pub fn to_json_schema(schema: &Schema) -> Value {
+    let mut out = Map::new();
+    out.insert("$schema".to_owned(), json!(DRAFT));
+
+    if let Value::Object(converted) = convert(schema, DEFS) {
+        out.extend(converted);
+    }
+
+    if !schema.definitions().is_empty() {
+        out.insert(
+            "$defs".to_owned(),
+            schema
+                .definitions()
+                .iter()
+                .map(|(name, sub_schema)| (name.clone(), convert(sub_schema, DEFS)))
+                .collect::<Map<_, _>>()
+                .into(),
+        );
+    }
+
+    Value::Object(out)
+}
+
+/// Converts a JSON Typedef schema, without its `definitions`, into JSON Schema.
+/// Refs become a `$ref` to the definition's name, prefixed by `refs`.
+pub(crate) fn convert(schema: &Schema, refs: &str) -> Value {
+    let mut out = match schema {
+        Schema::Empty { .. } => json!({}),
+        Schema::Ref { ref_, .. } => json!({ "$ref": format!("{}{}", refs, ref_) }),
+        Schema::Type { type_, .. } => convert_type(type_),
+        Schema::Enum { enum_, .. } => json!({ "enum": enum_ }),
+        Schema::Elements { elements, .. } => json!({
+            "type": "array",
+            "items": convert(elements, refs),
+        }),
+        Schema::Properties {
+            properties,
+            optional_properties,
+            additional_properties,
+            ..
+        } => convert_properties(
+            properties,
+            optional_properties,
+            *additional_properties,
+            refs,
+        ),
+        Schema::Values { values, .. } => json!({
+            "type": "object",
+            "additionalProperties": convert(values, refs),
+        }),
+        Schema::Discriminator {
+            discriminator,
+            mapping,
+            ..
+        } => {
+            let one_of: Vec<_> = mapping
+                .iter()
+                .map(|(tag, sub_schema)| {
+                    let mut branch = convert(sub_schema, refs);
+
+                    // Each branch is a properties form, so it has a
+                    // "properties" and a "required" array for the tag to go
+                    // in.
+                    branch["properties"][discriminator] = json!({ "const": tag });
+                    if let Some(required) = branch["required"].as_array_mut() {
+                        required.insert(0, json!(discriminator));
+                    } else {
+                        branch["required"] = json!([discriminator]);
+                    }
+
+                    branch
+                })
+                .collect();
+
+            json!({
+                "type": "object",
+                "required": [discriminator],
+                "oneOf": one_of,
+            })
+        }
+    };
+
+    if schema.nullable() {
+        out = make_nullable(out);
+    }
+
+    if let Some(description) = schema.metadata().get("description") {
+        out["description"] = description.clone();
+    }
+
+    out
+}
+
+fn convert_type(type_: &Type) -> Value {
+    match type_ {
+        Type::Boolean => json!({ "type": "boolean" }),
+        Type::String => json!({ "type": "string" }),
+        Type::Timestamp => json!({ "type": "string", "format": "date-time" }),
+        Type::Float32 => json!({ "type": "number", "format": "float" }),
+        Type::Float64 => json!({ "type": "number", "format": "double" }),
+        Type::Int8 => integer(i8::MIN as i64, i8::MAX as i64),
+        Type::Uint8 => integer(u8::MIN as i64, u8::MAX as i64),
+        Type::Int16 => integer(i16::MIN as i64, i16::MAX as i64),
+        Type::Uint16 => integer(u16::MIN as i64, u16::MAX as i64),
+        Type::Int32 => integer(i32::MIN as i64, i32::MAX as i64),
+        Type::Uint32 => integer(u32::MIN as i64, u32::MAX as i64),
+    }
+}
+
+fn integer(min: i64, max: i64) -> Value {
+    json!({ "type": "integer", "minimum": min, "maximum": max })
+}
+
+fn convert_properties(
+    properties: &BTreeMap<String, Schema>,
+    optional_properties: &BTreeMap<String, Schema>,
+    additional_properties: bool,
+    refs: &str,
+) -> Value {
+    let mut out = json!({ "type": "object" });
+
+    if !properties.is_empty() || !optional_properties.is_empty() {
+        out["properties"] = properties
+            .iter()
+            .chain(optional_properties)
+            .map(|(name, sub_schema)| (name.clone(), convert(sub_schema, refs)))
+            .collect::<Map<_, _>>()
+            .into();
+    }
+
+    if !properties.is_empty() {
+        out["required"] = properties.keys().cloned().collect::<Vec<_>>().into();
+    }
+
+    if !additional_properties {
+        out["additionalProperties"] = json!(false);
+    }
+
+    out
+}
+
+fn make_nullable(mut schema: Value) -> Value {
+    if let Some(enum_) = schema.get_mut("enum").and_then(Value::as_array_mut) {
+        enum_.push(Value::Null);
+        return schema;
+    }
+
+    // A "oneOf" can't simply also accept null, because null would satisfy
+    // every one of the branches.
+    if schema.get("oneOf").is_some() || schema.get("$ref").is_some() {
+        return json!({ "anyOf": [schema, { "type": "null" }] });
+    }
+
+    // Otherwise, the schema is either empty, and so already accepts null, or
+    // has a single "type" that can be turned into a union with null.
+    if let Some(type_) = schema.get_mut("type") {
+        *type_ = json!([type_.take(), "null"]);
+    }
+
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+
+    fn convert_jtd(schema: Value) -> Value {
+        let schema = jtd_schema(schema);
+
+        let mut out = to_json_schema(&schema);
+        out.as_object_mut().unwrap().remove("$schema");
+        out
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(json!({}), convert_jtd(json!({})));
+        assert_eq!(
+            json!({ "type": ["string", "null"], "format": "date-time" }),
+            convert_jtd(json!({ "type": "timestamp", "nullable": true }))
+        );
+        assert_eq!(
+            json!({ "type": "integer", "minimum": -2147483648i64, "maximum": 2147483647 }),
+            convert_jtd(json!({ "type": "int32" }))
+        );
+        assert_eq!(
+            json!({ "enum": ["a", "b", null] }),
+            convert_jtd(json!({ "enum": ["a", "b"], "nullable": true }))
+        );
+    }
+
+    #[test]
+    fn elements_and_values() {
+        assert_eq!(
+            json!({ "type": "array", "items": { "type": "boolean" }}),
+            convert_jtd(json!({ "elements": { "type": "boolean" }}))
+        );
+        assert_eq!(
+            json!({ "type": ["object", "null"], "additionalProperties": { "type": "string" }}),
+            convert_jtd(json!({ "values": { "type": "string" }, "nullable": true }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            json!({
+                "anyOf": [
+                    {
+                        "type": "object",
+                        "required": ["kind"],
+                        "oneOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "kind": { "const": "a" },
+                                    "x": { "type": "string" },
+                                },
+                                "required": ["kind", "x"],
+                                "additionalProperties": false,
+                            },
+                            {
+                                "type": "object",
+                                "properties": { "kind": { "const": "b" }},
+                                "required": ["kind"],
+                                "additionalProperties": false,
+                            },
+                        ],
+                    },
+                    { "type": "null" },
+                ],
+            }),
+            convert_jtd(json!({
+                "discriminator": "kind",
+                "mapping": {
+                    "a": { "properties": { "x": { "type": "string" }}},
+                    "b": { "properties": {}},
+                },
+                "nullable": true,
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions_and_refs() {
+        assert_eq!(
+            json!({
+                "anyOf": [{ "$ref": "#/$defs/a" }, { "type": "null" }],
+                "$defs": { "a": { "type": "boolean", "description": "xxx" }},
+            }),
+            convert_jtd(json!({
+                "definitions": {
+                    "a": { "type": "boolean", "metadata": { "description": "xxx" }},
+                },
+                "ref": "a",
+                "nullable": true,
+            }))
+        );
+    }
+}
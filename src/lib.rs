@@ -45,13 +45,34 @@
 //! )
 //! ```
 
+mod avro;
+mod bigquery;
+mod extended_json;
+mod har;
 mod hints;
 mod inferred_number;
 mod inferred_schema;
+mod json5_stream;
+mod json_schema;
+mod names;
+mod openapi;
+mod rust;
+mod stats;
+#[cfg(test)]
+mod test_util;
+mod typescript;
 
-pub use crate::hints::{HintSet, Hints};
-pub use crate::inferred_number::NumType;
+pub use crate::avro::to_avro;
+pub use crate::bigquery::{to_bigquery, to_bigquery_with_warnings};
+pub use crate::har::{har_examples, HarError, HarExample};
+pub use crate::hints::{HintMap, HintSet, Hints, TypeHint};
+pub use crate::inferred_number::{Headroom, NumType};
 use crate::inferred_schema::InferredSchema;
+pub use crate::json5_stream::split_json5;
+pub use crate::json_schema::to_json_schema;
+pub use crate::openapi::to_openapi;
+pub use crate::rust::to_rust;
+pub use crate::typescript::to_typescript;
 use jtd::Schema;
 use serde_json::Value;
 
@@ -85,10 +106,27 @@ impl<'a> Inferrer<'a> {
         }
     }
 
+    /// Returns warnings about parts of the inference that the inferred schema
+    /// can't represent well, such as integers too large for any of JSON
+    /// Typedef's integer types.
+    ///
+    /// Each warning starts with a JSON Pointer to the part of the input it's
+    /// about, or "the root" for the input as a whole.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.inference.warnings(&self.hints, "", &mut warnings);
+        warnings
+    }
+
     /// Converts the inference to a JSON Type Definition schema.
     ///
-    /// It is guaranteed that the resulting schema will accept all of the inputs
-    /// previously provided via [`Inferrer::infer`].
+    /// The resulting schema accepts all of the inputs previously provided via
+    /// [`Inferrer::infer`], unless hints asked for something the inputs don't
+    /// follow, such as numbers that fit in none of the allowed number types, a
+    /// type hint that some of the inputs contradict, or a property hinted as
+    /// required that some of the inputs omit. Each such case is reported by
+    /// [`Inferrer::warnings`], so an inference without warnings accepts all of
+    /// its inputs.
     pub fn into_schema(self) -> Schema {
         self.inference.into_schema(&self.hints)
     }
@@ -1,11 +1,23 @@
-use anyhow::Error;
+use anyhow::{bail, Error};
 use clap::{crate_version, load_yaml, App, AppSettings};
-use jtd_infer::{HintSet, Hints, Inferrer, NumType};
-use serde_json::Deserializer;
-use std::fs::File;
+use jtd::Schema;
+use jtd_infer::{
+    har_examples, split_json5, to_avro, to_bigquery_with_warnings, to_json_schema, to_openapi,
+    to_rust, to_typescript, Headroom, HintMap, HintSet, Hints, Inferrer, NumType, TypeHint,
+};
+use regex::Regex;
+use serde_json::{Deserializer, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
 use std::io::stdin;
+use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+#[cfg(unix)]
+use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+use tempfile::Builder;
 
 fn main() -> Result<(), Error> {
     let cli_yaml = load_yaml!("cli.yaml");
@@ -14,10 +26,31 @@ fn main() -> Result<(), Error> {
         .version(crate_version!())
         .get_matches();
 
-    let reader = BufReader::new(match matches.value_of("input").unwrap() {
-        "-" => Box::new(stdin()) as Box<dyn Read>,
-        file @ _ => Box::new(File::open(file)?) as Box<dyn Read>,
-    });
+    let extractor = if let Some(regex) = matches.value_of("extract-regex") {
+        Some(Extractor::Regex(Regex::new(regex)?))
+    } else {
+        matches
+            .value_of("after-prefix")
+            .map(|prefix| Extractor::Prefix(prefix.to_owned()))
+    };
+
+    let input_format = matches.value_of("input-format").unwrap();
+    if extractor.is_some() && input_format != "json" {
+        bail!("--extract-regex and --after-prefix only support the json input format");
+    }
+
+    let group_by = match matches.value_of("group-by") {
+        Some(_) if input_format == "har" => {
+            bail!("--group-by doesn't support the har input format, which is grouped by endpoint")
+        }
+        Some("file") => Some(GroupBy::File),
+        Some(pointer) if pointer.starts_with('/') => Some(GroupBy::Pointer(pointer.to_owned())),
+        Some(group_by) => bail!(
+            "--group-by must be file, or a JSON Pointer like /event: {}",
+            group_by
+        ),
+        None => None,
+    };
 
     let enum_hints: Vec<Vec<_>> = matches
         .values_of("enum-hint")
@@ -37,7 +70,459 @@ fn main() -> Result<(), Error> {
         .map(parse_json_pointer)
         .collect();
 
-    let default_num_type = match matches.value_of("default-number-type").unwrap() {
+    let nullable_hints: Vec<Vec<_>> = matches
+        .values_of("nullable-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let optional_hints: Vec<Vec<_>> = matches
+        .values_of("optional-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let required_hints: Vec<Vec<_>> = matches
+        .values_of("required-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let json_string_hints: Vec<Vec<_>> = matches
+        .values_of("json-string-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let redact_hints: Vec<Vec<_>> = matches
+        .values_of("redact-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let timestamp_hints: Vec<Vec<_>> = matches
+        .values_of("timestamp-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let no_timestamp_hints: Vec<Vec<_>> = matches
+        .values_of("no-timestamp-hint")
+        .unwrap_or_default()
+        .map(parse_json_pointer)
+        .collect();
+
+    let descriptions: Vec<(String, Vec<_>, String)> = match matches.value_of("descriptions") {
+        Some(path) => serde_yaml::from_reader::<_, BTreeMap<String, String>>(File::open(path)?)?
+            .into_iter()
+            .map(|(pointer, description)| {
+                let path = parse_json_pointer(&pointer);
+                (pointer, path, description)
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    let description_hints = HintMap::new(
+        descriptions
+            .iter()
+            .map(|(_, path, description)| (&path[..], description))
+            .collect(),
+    );
+
+    let stats_samples: usize = matches.value_of("stats-samples").unwrap().parse()?;
+
+    let min_timestamp_samples: usize =
+        matches.value_of("min-timestamp-samples").unwrap().parse()?;
+
+    let default_num_type = parse_num_type(matches.value_of("default-number-type").unwrap())?;
+
+    let allowed_num_types = matches
+        .values_of("allowed-number-types")
+        .unwrap_or_default()
+        .map(parse_num_type)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let num_type_hints = matches
+        .values_of("number-type-hint")
+        .unwrap_or_default()
+        .map(|hint| match hint.rsplit_once('=') {
+            Some((pointer, num_type)) => Ok((
+                pointer.to_owned(),
+                parse_json_pointer(pointer),
+                parse_num_type(num_type)?,
+            )),
+            None => bail!("--number-type-hint must look like /path=type: {}", hint),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let type_hints = matches
+        .values_of("type-hint")
+        .unwrap_or_default()
+        .map(|hint| match hint.rsplit_once('=') {
+            Some((pointer, type_)) => Ok((
+                pointer.to_owned(),
+                parse_json_pointer(pointer),
+                parse_type_hint(type_)?,
+            )),
+            None => bail!("--type-hint must look like /path=type: {}", hint),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let type_hint_map = HintMap::new(
+        type_hints
+            .iter()
+            .map(|(_, path, type_)| (&path[..], type_))
+            .collect(),
+    );
+
+    let headroom = match matches.value_of("headroom") {
+        Some(headroom) => parse_headroom(headroom)?,
+        None => Headroom::None,
+    };
+
+    let num_type_hint_map = HintMap::new(
+        num_type_hints
+            .iter()
+            .map(|(_, path, num_type)| (&path[..], num_type))
+            .collect(),
+    );
+
+    let hints = Hints::new(
+        default_num_type,
+        HintSet::new(enum_hints.iter().map(|p| &p[..]).collect()),
+        HintSet::new(values_hints.iter().map(|p| &p[..]).collect()),
+        HintSet::new(discriminator_hints.iter().map(|p| &p[..]).collect()),
+    )
+    .with_nullable_hints(HintSet::new(
+        nullable_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_optional_hints(HintSet::new(
+        optional_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_required_hints(HintSet::new(
+        required_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_extended_json(matches.is_present("extended-json"))
+    .with_json_strings(matches.is_present("json-strings"))
+    .with_json_string_hints(HintSet::new(
+        json_string_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_descriptions(description_hints.clone())
+    .with_allowed_num_types(&allowed_num_types)
+    .with_num_type_hints(num_type_hint_map.clone())
+    .with_headroom(headroom)
+    .with_type_hints(type_hint_map.clone())
+    .with_timestamps(!matches.is_present("no-timestamps"))
+    .with_timestamp_hints(HintSet::new(
+        timestamp_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_no_timestamp_hints(HintSet::new(
+        no_timestamp_hints.iter().map(|p| &p[..]).collect(),
+    ))
+    .with_min_timestamp_samples(min_timestamp_samples)
+    .with_float_notation(matches.is_present("float-notation"))
+    .with_float32(matches.is_present("float32"))
+    .with_int64_metadata(matches.is_present("int64-metadata"))
+    .with_decimal_metadata(matches.is_present("decimal-metadata"))
+    .with_stats_samples(stats_samples)
+    .with_redacted_samples(matches.is_present("redact-samples"))
+    .with_redact_hints(HintSet::new(redact_hints.iter().map(|p| &p[..]).collect()));
+
+    let hints = if matches.is_present("stats") {
+        hints.with_stats(matches.value_of("stats-key").unwrap())
+    } else {
+        hints
+    };
+
+    let is_named = group_by.is_some() || input_format == "har";
+    let mut groups = Groups::new(hints.clone(), group_by);
+
+    for input in matches.values_of("input").unwrap() {
+        let mut reader = BufReader::new(match input {
+            "-" => Box::new(stdin()) as Box<dyn Read>,
+            file => Box::new(File::open(file)?) as Box<dyn Read>,
+        });
+
+        match input_format {
+            "json" => {
+                if let Some(extractor) = &extractor {
+                    let mut skipped = 0;
+                    for line in reader.lines() {
+                        let line = line?;
+                        match extractor
+                            .extract(&line)
+                            .and_then(|json| Deserializer::from_str(json).into_iter().next())
+                            .and_then(Result::ok)
+                        {
+                            Some(value) => groups.infer(input, value),
+                            None => skipped += 1,
+                        }
+                    }
+
+                    if skipped > 0 {
+                        eprintln!(
+                            "jtd-infer: skipped {} line(s) with no JSON document",
+                            skipped
+                        );
+                    }
+                } else {
+                    for value in Deserializer::from_reader(reader).into_iter() {
+                        groups.infer(input, value?);
+                    }
+                }
+            }
+            "json5" => {
+                let mut input_text = String::new();
+                reader.read_to_string(&mut input_text)?;
+
+                for document in split_json5(&input_text) {
+                    groups.infer(input, json5::from_str(document)?);
+                }
+            }
+            "har" => {
+                let har: Value = serde_json::from_reader(reader)?;
+                for example in har_examples(&har)? {
+                    groups.infer_named(example.name, example.value);
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    let schema = groups.into_schema(is_named);
+
+    for i in description_hints.unmatched() {
+        eprintln!(
+            "jtd-infer: description for {} matched nothing in the input",
+            descriptions[i].0
+        );
+    }
+
+    for i in num_type_hint_map.unmatched() {
+        eprintln!(
+            "jtd-infer: number type hint for {} matched no numbers in the input",
+            num_type_hints[i].0
+        );
+    }
+
+    for i in type_hint_map.unmatched() {
+        eprintln!(
+            "jtd-infer: type hint for {} matched nothing in the input",
+            type_hints[i].0
+        );
+    }
+
+    // Everything is converted to a Value before being serialized, so that keys
+    // are always output in sorted order.
+    let pretty = matches.is_present("pretty");
+    let output = match matches.value_of("output-format").unwrap() {
+        "jtd" => to_json(&serde_json::to_value(schema.into_serde_schema())?, pretty)?,
+        "json-schema" => to_json(&to_json_schema(&schema), pretty)?,
+        "typescript" => to_typescript(&schema),
+        "rust" => to_rust(&schema),
+        "avro" => to_json(&to_avro(&schema), pretty)?,
+        "bigquery" => {
+            let (table, warnings) = to_bigquery_with_warnings(&schema);
+            for warning in warnings {
+                eprintln!("jtd-infer: warning: {}", warning);
+            }
+
+            to_json(&table, pretty)?
+        }
+        "openapi" => to_json(&to_openapi(&schema), pretty)?,
+        "yaml" => to_yaml(&serde_json::to_value(schema.into_serde_schema())?)?,
+        _ => unreachable!(),
+    };
+
+    match matches.value_of("output") {
+        Some(path) => write_atomically(path, &output)?,
+        None => println!("{}", output.trim_end()),
+    }
+
+    Ok(())
+}
+
+enum Extractor {
+    Regex(Regex),
+    Prefix(String),
+}
+
+impl Extractor {
+    fn extract<'a>(&self, line: &'a str) -> Option<&'a str> {
+        match self {
+            Self::Regex(regex) => regex
+                .captures(line)
+                .and_then(|captures| captures.name("json").or_else(|| captures.get(0)))
+                .map(|json| json.as_str()),
+            Self::Prefix(prefix) => line
+                .find(prefix.as_str())
+                .map(|i| &line[i + prefix.len()..]),
+        }
+    }
+}
+
+/// What to group examples by, so that each group gets a schema of its own.
+enum GroupBy {
+    /// The name of the file the examples are from, without its extension.
+    File,
+
+    /// The string at a JSON Pointer in each example, such as an event name.
+    Pointer(String),
+}
+
+/// Examples grouped into named schemas, for --group-by and HAR files.
+struct Groups<'a> {
+    hints: Hints<'a>,
+    group_by: Option<GroupBy>,
+    inferrers: BTreeMap<String, Inferrer<'a>>,
+
+    /// How many examples had nothing at the JSON Pointer to group them by.
+    ungrouped: usize,
+}
+
+impl<'a> Groups<'a> {
+    fn new(hints: Hints<'a>, group_by: Option<GroupBy>) -> Self {
+        Self {
+            hints,
+            group_by,
+            inferrers: BTreeMap::new(),
+            ungrouped: 0,
+        }
+    }
+
+    /// Infers from `value`, an example read from `input`, in the group given
+    /// by --group-by.
+    fn infer(&mut self, input: &str, value: Value) {
+        let name = match &self.group_by {
+            None => String::new(),
+            Some(GroupBy::File) => match input {
+                "-" => "stdin".to_owned(),
+                file => Path::new(file)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| file.to_owned()),
+            },
+            Some(GroupBy::Pointer(pointer)) => match value.pointer(pointer) {
+                Some(Value::String(name)) => name.clone(),
+                _ => {
+                    self.ungrouped += 1;
+                    return;
+                }
+            },
+        };
+
+        self.infer_named(name, value);
+    }
+
+    fn infer_named(&mut self, name: String, value: Value) {
+        let inferrer = self
+            .inferrers
+            .remove(&name)
+            .unwrap_or_else(|| Inferrer::new(self.hints.clone()));
+
+        self.inferrers.insert(name, inferrer.infer(value));
+    }
+
+    /// Converts the groups into a schema. If `is_named`, each group is one of
+    /// its definitions, and otherwise all examples are in a single group, which
+    /// is the schema itself.
+    fn into_schema(mut self, is_named: bool) -> Schema {
+        if let Some(GroupBy::Pointer(pointer)) = &self.group_by {
+            if self.ungrouped > 0 {
+                eprintln!(
+                    "jtd-infer: skipped {} example(s) with no string at {}",
+                    self.ungrouped, pointer
+                );
+            }
+        }
+
+        if !is_named {
+            let inferrer = self
+                .inferrers
+                .remove("")
+                .unwrap_or_else(|| Inferrer::new(self.hints));
+
+            return into_schema(inferrer, None);
+        }
+
+        Schema::Empty {
+            definitions: self
+                .inferrers
+                .into_iter()
+                .map(|(name, inferrer)| {
+                    let schema = into_schema(inferrer, Some(&name));
+                    (name, schema)
+                })
+                .collect(),
+            metadata: Default::default(),
+        }
+    }
+}
+
+/// Converts an inference into a schema, after printing any warnings about it.
+/// `name` is the name of the schema, if there are several.
+fn into_schema(inferrer: Inferrer, name: Option<&str>) -> Schema {
+    for warning in inferrer.warnings() {
+        match name {
+            Some(name) => eprintln!("jtd-infer: warning: {}: {}", name, warning),
+            None => eprintln!("jtd-infer: warning: {}", warning),
+        }
+    }
+
+    inferrer.into_schema()
+}
+
+fn to_json(value: &Value, pretty: bool) -> Result<String, Error> {
+    Ok(if pretty {
+        serde_json::to_string_pretty(value)?
+    } else {
+        serde_json::to_string(value)?
+    })
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
+fn to_yaml(value: &Value) -> Result<String, Error> {
+    Ok(serde_yaml::to_string(value)?)
+}
+
+/// With arbitrary precision, numbers are kept as the text they were written as,
+/// which only serde_json knows how to serialize, so YAML is converted from JSON.
+#[cfg(feature = "arbitrary_precision")]
+fn to_yaml(value: &Value) -> Result<String, Error> {
+    let value: serde_yaml::Value = serde_yaml::from_str(&to_json(value, false)?)?;
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+/// Writes `output` to a temporary file next to `path`, and then renames it to
+/// `path`, so that readers never see a partially-written file.
+fn write_atomically(path: &str, output: &str) -> Result<(), Error> {
+    let path = Path::new(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    // Temporary files are only readable by their owner by default. A new file
+    // should instead get the usual permissions, as limited by the umask, and
+    // an existing one should keep its permissions.
+    let mut builder = Builder::new();
+    #[cfg(unix)]
+    builder.permissions(Permissions::from_mode(0o666));
+
+    let mut file = builder.tempfile_in(dir)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    writeln!(file, "{}", output.trim_end())?;
+    file.persist(path)?;
+
+    Ok(())
+}
+
+fn parse_num_type(s: &str) -> Result<NumType, Error> {
+    Ok(match s {
         "int8" => NumType::Int8,
         "uint8" => NumType::Uint8,
         "int16" => NumType::Int16,
@@ -46,31 +531,50 @@ fn main() -> Result<(), Error> {
         "uint32" => NumType::Uint32,
         "float32" => NumType::Float32,
         "float64" => NumType::Float64,
-        _ => unreachable!(),
-    };
+        _ => bail!("unknown number type: {}", s),
+    })
+}
 
-    let hints = Hints::new(
-        default_num_type,
-        HintSet::new(enum_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(values_hints.iter().map(|p| &p[..]).collect()),
-        HintSet::new(discriminator_hints.iter().map(|p| &p[..]).collect()),
-    );
+fn parse_type_hint(s: &str) -> Result<TypeHint, Error> {
+    Ok(match s {
+        "string" => TypeHint::String,
+        "timestamp" => TypeHint::Timestamp,
+        "boolean" => TypeHint::Boolean,
+        "any" => TypeHint::Any,
+        _ => match parse_num_type(s) {
+            Ok(num_type) => TypeHint::Number(num_type),
+            Err(_) => bail!("unknown type: {}", s),
+        },
+    })
+}
 
-    let mut inferrer = Inferrer::new(hints);
+fn parse_headroom(s: &str) -> Result<Headroom, Error> {
+    if s == "next-size" {
+        return Ok(Headroom::NextSize);
+    }
 
-    let stream = Deserializer::from_reader(reader);
-    for value in stream.into_iter() {
-        inferrer = inferrer.infer(value?);
+    if let Some(factor) = s.strip_suffix('x').and_then(|f| f.parse::<f64>().ok()) {
+        if factor >= 1.0 {
+            return Ok(Headroom::Factor(factor));
+        }
     }
 
-    let serde_schema: jtd::SerdeSchema = inferrer.into_schema().into_serde_schema();
-    println!("{}", serde_json::to_string(&serde_schema)?);
+    // JSON Typedef has no integer types wider than 32 bits.
+    match s {
+        "8-bit" => return Ok(Headroom::MinBits(8)),
+        "16-bit" => return Ok(Headroom::MinBits(16)),
+        "32-bit" => return Ok(Headroom::MinBits(32)),
+        _ => {}
+    }
 
-    Ok(())
+    bail!(
+        "--headroom must be next-size, a factor like 10x, or 8-bit, 16-bit or 32-bit: {}",
+        s
+    )
 }
 
 fn parse_json_pointer(s: &str) -> Vec<String> {
-    if s == "" {
+    if s.is_empty() {
         vec![]
     } else {
         s.replace("~1", "/")
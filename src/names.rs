@@ -0,0 +1,136 @@
+use std::collections::BTreeSet;
+
+/// Splits a name into words, on non-alphanumeric characters and on the
+/// boundaries of camelCase words.
+fn words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+
+    for c in s.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+
+            prev_lower = false;
+            continue;
+        }
+
+        if c.is_ascii_uppercase() && prev_lower && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// Converts a name to PascalCase, like `fooBar` or `foo_bar` to `FooBar`.
+pub fn pascal_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first)
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Converts a name to snake_case, like `fooBar` or `FooBar` to `foo_bar`.
+pub fn snake_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Converts a name to a PascalCase type name, prefixing it with `T` if it
+/// doesn't start with a letter.
+pub fn type_name(s: &str) -> String {
+    let name = pascal_case(s);
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("T{}", name)
+    }
+}
+
+/// Converts a name into an identifier that starts with a letter or underscore,
+/// and otherwise only contains letters, digits and underscores.
+///
+/// Unlike the other conversions here, this keeps the name's case and word
+/// boundaries as close to the original as possible.
+pub fn identifier(s: &str) -> String {
+    let name: String = s
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("_{}", name)
+    }
+}
+
+/// Keeps track of the names already given to generated declarations, so that
+/// each one is unique.
+#[derive(Default)]
+pub struct Names {
+    used: BTreeSet<String>,
+}
+
+impl Names {
+    /// Returns `name` if it's not yet been used, or else `name` with the
+    /// smallest numeric suffix that makes it unique.
+    pub fn unique(&mut self, name: &str) -> String {
+        let mut unique = name.to_owned();
+        let mut suffix = 2;
+        while self.used.contains(&unique) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+
+        self.used.insert(unique.clone());
+        unique
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases() {
+        assert_eq!("FooBar", pascal_case("fooBar"));
+        assert_eq!("FooBar", pascal_case("foo_bar"));
+        assert_eq!("UserId", pascal_case("userID"));
+        assert_eq!("GetUsersIdResponse", pascal_case("get_users_id_response"));
+        assert_eq!("foo_bar", snake_case("fooBar"));
+        assert_eq!("foo_bar", snake_case("FooBar"));
+        assert_eq!("foo_bar_2", snake_case("foo-bar 2"));
+        assert_eq!("", snake_case("$"));
+        assert_eq!("zip_Code", identifier("zip-Code"));
+        assert_eq!("_1st", identifier("1st"));
+    }
+
+    #[test]
+    fn unique() {
+        let mut names = Names::default();
+        assert_eq!("Foo", names.unique("Foo"));
+        assert_eq!("Foo2", names.unique("Foo"));
+        assert_eq!("Foo3", names.unique("Foo"));
+        assert_eq!("Bar", names.unique("Bar"));
+    }
+}
@@ -0,0 +1,400 @@
+use crate::json_schema::convert;
+use crate::names::{pascal_case, type_name, Names};
+use jtd::Schema;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+const VERSION: &str = "3.1.0";
+const REFS: &str = "#/components/schemas/";
+
+/// Converts a JSON Typedef schema into an OpenAPI 3.1 document whose
+/// `components.schemas` hold the schema and its definitions.
+///
+/// Each of the `definitions` becomes a component of the same name, and refs to
+/// them become a `$ref` to that component. The root schema becomes a component
+/// named `Root`, unless the root schema is empty but has `definitions`, in
+/// which case only the definitions are output. The schemas themselves are
+/// converted the same way as by [`to_json_schema`][`crate::to_json_schema`].
+///
+/// Objects that appear more than once, in the same component or in different
+/// ones, are shared: they become a component of their own, named after the
+/// property they first appear in, and each appearance becomes a `$ref` to it.
+/// Metadata, such as descriptions or statistics, doesn't keep objects from
+/// being shared. Each appearance keeps its own top-level metadata, and the
+/// component has the nested metadata of the first appearance.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_openapi;
+///
+/// let user = json!({ "properties": { "name": { "type": "string" }}});
+/// let schema = json!({
+///     "definitions": {
+///         "get_user_response": { "properties": { "user": user }},
+///         "put_user_request": { "properties": { "user": user }},
+///     },
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     json!({
+///         "openapi": "3.1.0",
+///         "info": { "title": "Inferred schemas", "version": "0.0.0" },
+///         "components": {
+///             "schemas": {
+///                 "User": {
+///                     "type": "object",
+///                     "properties": { "name": { "type": "string" }},
+///                     "required": ["name"],
+///                     "additionalProperties": false,
+///                 },
+///                 "get_user_response": {
+///                     "type": "object",
+///                     "properties": { "user": { "$ref": "#/components/schemas/User" }},
+///                     "required": ["user"],
+///                     "additionalProperties": false,
+///                 },
+///                 "put_user_request": {
+///                     "type": "object",
+///                     "properties": { "user": { "$ref": "#/components/schemas/User" }},
+///                     "required": ["user"],
+///                     "additionalProperties": false,
+///                 },
+///             },
+///         },
+///     }),
+///     to_openapi(&schema),
+/// );
+/// ```
+pub fn to_openapi(schema: &Schema) -> Value {
+    let mut names = Names::default();
+    let mut components = Vec::new();
+
+    for (name, sub_schema) in schema.definitions() {
+        names.unique(name);
+        components.push((name.clone(), sub_schema.clone()));
+    }
+
+    let is_definitions_only =
+        matches!(schema, Schema::Empty { .. }) && !schema.definitions().is_empty();
+
+    if !is_definitions_only {
+        components.push((names.unique("Root"), schema.clone()));
+    }
+
+    let mut sharer = Sharer {
+        names,
+        counts: BTreeMap::new(),
+        shared: BTreeMap::new(),
+        definitions: Vec::new(),
+    };
+
+    for (_, component) in &components {
+        sharer.count(component, false);
+    }
+
+    for (name, component) in &mut components {
+        sharer.share(component, name, false);
+    }
+
+    let schemas: Map<_, _> = components
+        .iter()
+        .chain(&sharer.definitions)
+        .map(|(name, component)| (name.clone(), convert(component, REFS)))
+        .collect();
+
+    json!({
+        "openapi": VERSION,
+        "info": { "title": "Inferred schemas", "version": "0.0.0" },
+        "components": { "schemas": schemas },
+    })
+}
+
+/// Finds objects that appear more than once, and moves them into definitions
+/// of their own.
+struct Sharer {
+    names: Names,
+
+    /// How many times each shareable object appears, by its shape.
+    counts: BTreeMap<String, usize>,
+
+    /// The name of the definition for each shared object, by its shape.
+    shared: BTreeMap<String, String>,
+    definitions: Vec<(String, Schema)>,
+}
+
+impl Sharer {
+    fn count(&mut self, schema: &Schema, nested: bool) {
+        if let Some(shape) = shape(schema, nested) {
+            *self.counts.entry(shape).or_default() += 1;
+        }
+
+        for sub_schema in sub_schemas(schema) {
+            self.count(sub_schema, true);
+        }
+    }
+
+    /// Replaces shared objects in `schema` with refs. `name` is what to name a
+    /// definition for `schema`.
+    fn share(&mut self, schema: &mut Schema, name: &str, nested: bool) {
+        // The shape is taken before anything inside of the object is shared,
+        // so that it's the same as when the objects were counted.
+        let shape = shape(schema, nested).filter(|shape| self.counts[shape] > 1);
+
+        match schema {
+            Schema::Elements { elements, .. } => {
+                self.share(elements, &format!("{}Element", name), true)
+            }
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => {
+                for (key, sub_schema) in properties.iter_mut().chain(optional_properties) {
+                    self.share(sub_schema, key, true);
+                }
+            }
+            Schema::Values { values, .. } => self.share(values, &format!("{}Value", name), true),
+            Schema::Discriminator { mapping, .. } => {
+                // The mapping must contain objects, rather than refs to them.
+                for (tag, sub_schema) in mapping {
+                    self.share(sub_schema, &format!("{}{}", name, pascal_case(tag)), false);
+                }
+            }
+            _ => {}
+        }
+
+        let shape = match shape {
+            Some(shape) => shape,
+            None => return,
+        };
+
+        let ref_ = match self.shared.get(&shape) {
+            Some(ref_) => ref_.clone(),
+            None => {
+                let ref_ = self.names.unique(&type_name(name));
+                self.definitions.push((ref_.clone(), bare(schema)));
+                self.shared.insert(shape, ref_.clone());
+                ref_
+            }
+        };
+
+        // Each appearance keeps its own description and nullability.
+        *schema = Schema::Ref {
+            definitions: Default::default(),
+            metadata: schema.metadata().clone(),
+            nullable: schema.nullable(),
+            ref_,
+        };
+    }
+}
+
+fn sub_schemas(schema: &Schema) -> Vec<&Schema> {
+    match schema {
+        Schema::Elements { elements, .. } => vec![elements],
+        Schema::Properties {
+            properties,
+            optional_properties,
+            ..
+        } => properties
+            .values()
+            .chain(optional_properties.values())
+            .collect(),
+        Schema::Values { values, .. } => vec![values],
+        Schema::Discriminator { mapping, .. } => mapping.values().collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns a key that's the same for objects with the same properties, if
+/// `schema` is an object that could be shared. Only `nested` objects are
+/// shared, since the others are already components, or must stay objects.
+fn shape(schema: &Schema, nested: bool) -> Option<String> {
+    match schema {
+        Schema::Properties {
+            properties,
+            optional_properties,
+            ..
+        } if nested && !(properties.is_empty() && optional_properties.is_empty()) => {
+            let mut shape = bare(schema);
+            strip_metadata(&mut shape);
+
+            Some(serde_json::to_string(&shape.into_serde_schema()).expect("serialize shape"))
+        }
+        _ => None,
+    }
+}
+
+/// Removes the metadata, such as descriptions and statistics, from `schema`
+/// and everything in it, so that it doesn't keep objects with the same
+/// properties from being shared.
+fn strip_metadata(schema: &mut Schema) {
+    match schema {
+        Schema::Empty { metadata, .. }
+        | Schema::Ref { metadata, .. }
+        | Schema::Type { metadata, .. }
+        | Schema::Enum { metadata, .. } => metadata.clear(),
+        Schema::Elements {
+            metadata, elements, ..
+        } => {
+            metadata.clear();
+            strip_metadata(elements);
+        }
+        Schema::Properties {
+            metadata,
+            properties,
+            optional_properties,
+            ..
+        } => {
+            metadata.clear();
+            for sub_schema in properties
+                .values_mut()
+                .chain(optional_properties.values_mut())
+            {
+                strip_metadata(sub_schema);
+            }
+        }
+        Schema::Values {
+            metadata, values, ..
+        } => {
+            metadata.clear();
+            strip_metadata(values);
+        }
+        Schema::Discriminator {
+            metadata, mapping, ..
+        } => {
+            metadata.clear();
+            for sub_schema in mapping.values_mut() {
+                strip_metadata(sub_schema);
+            }
+        }
+    }
+}
+
+/// Returns `schema` without its description or nullability, which are kept
+/// where it's referred to instead.
+fn bare(schema: &Schema) -> Schema {
+    let mut schema = schema.clone();
+    if let Schema::Properties {
+        metadata, nullable, ..
+    } = &mut schema
+    {
+        metadata.clear();
+        *nullable = false;
+    }
+
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+
+    fn convert_jtd(schema: Value) -> Value {
+        let schema = jtd_schema(schema);
+
+        to_openapi(&schema)["components"]["schemas"].take()
+    }
+
+    #[test]
+    fn root() {
+        assert_eq!(
+            json!({ "Root": { "type": "array", "items": { "type": "boolean" }}}),
+            convert_jtd(json!({ "elements": { "type": "boolean" }}))
+        );
+    }
+
+    #[test]
+    fn definitions_and_refs() {
+        assert_eq!(
+            json!({
+                "Root": { "type": "string" },
+                "Root2": {
+                    "type": "object",
+                    "properties": {
+                        "a": { "$ref": "#/components/schemas/Root" },
+                    },
+                    "required": ["a"],
+                    "additionalProperties": false,
+                },
+            }),
+            convert_jtd(json!({
+                "definitions": { "Root": { "type": "string" }},
+                "properties": { "a": { "ref": "Root" }},
+            }))
+        );
+    }
+
+    #[test]
+    fn shared_objects() {
+        let point = json!({ "properties": { "x": { "type": "int32" }, "y": { "type": "int32" }}});
+        let shared = json!({ "$ref": "#/components/schemas/End" });
+        let int32 = json!({ "type": "integer", "minimum": -2147483648i64, "maximum": 2147483647 });
+
+        assert_eq!(
+            json!({
+                "Root": {
+                    "type": "object",
+                    "properties": {
+                        "start": shared,
+                        "end": { "anyOf": [shared, { "type": "null" }], "description": "last" },
+                        "points": { "type": "array", "items": shared },
+                        "size": {
+                            "type": "object",
+                            "properties": { "x": int32 },
+                            "required": ["x"],
+                            "additionalProperties": false,
+                        },
+                    },
+                    "required": ["end", "points", "size", "start"],
+                    "additionalProperties": false,
+                },
+                "End": {
+                    "type": "object",
+                    "properties": { "x": int32, "y": int32 },
+                    "required": ["x", "y"],
+                    "additionalProperties": false,
+                },
+            }),
+            convert_jtd(json!({
+                "properties": {
+                    "start": point,
+                    "end": {
+                        "properties": point["properties"],
+                        "nullable": true,
+                        "metadata": { "description": "last" },
+                    },
+                    "points": { "elements": point },
+                    "size": { "properties": { "x": { "type": "int32" }}},
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn shared_objects_with_nested_metadata() {
+        let point = |count: u64| {
+            json!({
+                "properties": {
+                    "x": { "type": "int32", "metadata": { "stats": { "count": count }}},
+                },
+            })
+        };
+
+        let schemas = convert_jtd(json!({
+            "properties": { "start": point(1), "end": point(2) },
+        }));
+
+        assert_eq!(
+            json!({ "$ref": "#/components/schemas/End" }),
+            schemas["Root"]["properties"]["start"]
+        );
+        assert_eq!(
+            json!({ "$ref": "#/components/schemas/End" }),
+            schemas["Root"]["properties"]["end"]
+        );
+    }
+}
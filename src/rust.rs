@@ -0,0 +1,478 @@
+use crate::names::{pascal_case, snake_case, type_name, Names};
+use jtd::{Schema, Type};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Converts a JSON Typedef schema into Rust type declarations that use `serde`
+/// to serialize and deserialize.
+///
+/// The root schema is declared as `Root`, and each of the `definitions` is
+/// declared using a PascalCase version of its name. If the root schema is
+/// empty but has `definitions`, then only the definitions are declared.
+///
+/// Objects are declared as structs named after the path to them. Fields are
+/// renamed to snake_case, with a `#[serde(rename)]` to keep the original name.
+/// Optional and nullable values become an [`Option`], the values form becomes
+/// a [`HashMap`][`std::collections::HashMap`], the enum form a fieldless
+/// enum, and the discriminator form an enum with `#[serde(tag)]`. A `rustType`
+/// in the `metadata` of a type form overrides the Rust type used for it.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_rust;
+///
+/// let schema = json!({
+///     "properties": { "userName": { "type": "string" }},
+///     "optionalProperties": { "age": { "type": "uint8" }},
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     "use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// pub struct Root {
+///     #[serde(skip_serializing_if = \"Option::is_none\")]
+///     pub age: Option<u8>,
+///
+///     #[serde(rename = \"userName\")]
+///     pub user_name: String,
+/// }
+/// ",
+///     to_rust(&schema),
+/// );
+/// ```
+pub fn to_rust(schema: &Schema) -> String {
+    let mut emitter = Emitter::default();
+
+    for name in schema.definitions().keys() {
+        let type_name = emitter.names.unique(&struct_name(name));
+        emitter.refs.insert(name.clone(), type_name);
+    }
+
+    for (name, sub_schema) in schema.definitions() {
+        let type_name = emitter.refs[name].clone();
+        emitter.declare(&type_name, sub_schema);
+    }
+
+    let is_definitions_only =
+        matches!(schema, Schema::Empty { .. }) && !schema.definitions().is_empty();
+
+    if !is_definitions_only {
+        let root = emitter.names.unique("Root");
+        emitter.declare(&root, schema);
+    }
+
+    let mut imports = Vec::new();
+    if emitter.uses_timestamps {
+        imports.push("use chrono::{DateTime, FixedOffset};\n".to_owned());
+    }
+
+    imports.push("use serde::{Deserialize, Serialize};\n".to_owned());
+
+    if emitter.uses_hash_map {
+        imports.push("use std::collections::HashMap;\n".to_owned());
+    }
+
+    let mut out = imports.concat();
+    for decl in emitter.decls {
+        out.push('\n');
+        out.push_str(&decl);
+    }
+
+    out
+}
+
+const DERIVES: &str = "#[derive(Debug, Clone, Serialize, Deserialize)]\n";
+
+/// The strict and reserved keywords from the Rust reference, which can't be
+/// used as identifiers. `Self` is the only one that's PascalCase.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+fn field_name(name: &str) -> String {
+    let name = snake_case(name);
+    if name.is_empty() {
+        "field".to_owned()
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+fn variant_name(name: &str) -> String {
+    let name = pascal_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("V{}", name)
+    }
+}
+
+fn struct_name(name: &str) -> String {
+    let name = type_name(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn rename(original: &str, name: &str) -> String {
+    if original == name {
+        String::new()
+    } else {
+        format!(
+            "    #[serde(rename = {})]\n",
+            serde_json::to_string(original).unwrap()
+        )
+    }
+}
+
+#[derive(Default)]
+struct Emitter {
+    names: Names,
+    refs: BTreeMap<String, String>,
+    decls: Vec<String>,
+    uses_timestamps: bool,
+    uses_hash_map: bool,
+}
+
+impl Emitter {
+    /// Declares `schema` under a name that has already been reserved.
+    fn declare(&mut self, name: &str, schema: &Schema) {
+        match schema {
+            Schema::Enum { .. } | Schema::Properties { .. } | Schema::Discriminator { .. }
+                if !schema.nullable() =>
+            {
+                self.declare_named(name, schema);
+            }
+            _ => {
+                let index = self.reserve_decl();
+                let expr = self.expr(name, schema);
+                self.decls[index] = format!("pub type {} = {};\n", name, expr);
+            }
+        }
+    }
+
+    /// Returns a Rust type for `schema`, declaring any structs and enums it
+    /// needs with names derived from `name`.
+    fn expr(&mut self, name: &str, schema: &Schema) -> String {
+        let expr = match schema {
+            Schema::Empty { .. } => return "serde_json::Value".to_owned(),
+            Schema::Ref { ref_, .. } => self.refs[ref_].clone(),
+            // Code generators let metadata override the type to use for a
+            // primitive.
+            Schema::Type { metadata, .. }
+                if metadata.get("rustType").is_some_and(Value::is_string) =>
+            {
+                metadata["rustType"].as_str().unwrap().to_owned()
+            }
+            Schema::Type { type_, .. } => match type_ {
+                Type::Boolean => "bool",
+                Type::String => "String",
+                Type::Timestamp => {
+                    self.uses_timestamps = true;
+                    "DateTime<FixedOffset>"
+                }
+                Type::Int8 => "i8",
+                Type::Uint8 => "u8",
+                Type::Int16 => "i16",
+                Type::Uint16 => "u16",
+                Type::Int32 => "i32",
+                Type::Uint32 => "u32",
+                Type::Float32 => "f32",
+                Type::Float64 => "f64",
+            }
+            .to_owned(),
+            Schema::Elements { elements, .. } => {
+                format!("Vec<{}>", self.expr(&format!("{}Element", name), elements))
+            }
+            Schema::Values { values, .. } => {
+                self.uses_hash_map = true;
+                format!(
+                    "HashMap<String, {}>",
+                    self.expr(&format!("{}Value", name), values)
+                )
+            }
+            Schema::Enum { .. } | Schema::Properties { .. } | Schema::Discriminator { .. } => {
+                let name = self.names.unique(name);
+                self.declare_named(&name, schema);
+                name
+            }
+        };
+
+        if schema.nullable() {
+            format!("Option<{}>", expr)
+        } else {
+            expr
+        }
+    }
+
+    fn declare_named(&mut self, name: &str, schema: &Schema) {
+        match schema {
+            Schema::Enum { enum_, .. } => {
+                let mut variants = Names::default();
+                let mut decl = format!("{}pub enum {} {{\n", DERIVES, name);
+                for value in enum_ {
+                    let variant = variants.unique(&variant_name(value));
+                    decl.push_str(&rename(value, &variant));
+                    decl.push_str(&format!("    {},\n", variant));
+                }
+
+                decl.push_str("}\n");
+                self.decls.push(decl);
+            }
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => self.declare_struct(name, properties, optional_properties),
+            Schema::Discriminator {
+                discriminator,
+                mapping,
+                ..
+            } => {
+                let index = self.reserve_decl();
+
+                let mut decl = format!(
+                    "{}#[serde(tag = {})]\npub enum {} {{\n",
+                    DERIVES,
+                    serde_json::to_string(discriminator).unwrap(),
+                    name
+                );
+
+                let mut variants = Names::default();
+                for (tag, sub_schema) in mapping {
+                    let variant = variants.unique(&variant_name(tag));
+                    let struct_name = self.names.unique(&format!("{}{}", name, variant));
+
+                    if let Schema::Properties {
+                        properties,
+                        optional_properties,
+                        ..
+                    } = sub_schema
+                    {
+                        self.declare_struct(&struct_name, properties, optional_properties);
+                    }
+
+                    decl.push_str(&rename(tag, &variant));
+                    decl.push_str(&format!("    {}({}),\n", variant, struct_name));
+                }
+
+                decl.push_str("}\n");
+                self.decls[index] = decl;
+            }
+            _ => unreachable!("declared non-named type"),
+        }
+    }
+
+    fn declare_struct(
+        &mut self,
+        name: &str,
+        properties: &BTreeMap<String, Schema>,
+        optional_properties: &BTreeMap<String, Schema>,
+    ) {
+        let index = self.reserve_decl();
+
+        let fields = properties
+            .iter()
+            .map(|field| (field, false))
+            .chain(optional_properties.iter().map(|field| (field, true)));
+
+        let mut fields: Vec<_> = fields.collect();
+        fields.sort_by_key(|((key, _), _)| key.as_str());
+
+        let mut field_names = Names::default();
+        let mut decls = Vec::new();
+        for ((key, sub_schema), optional) in fields {
+            let field = field_names.unique(&field_name(key));
+            let mut expr = self.expr(&format!("{}{}", name, pascal_case(key)), sub_schema);
+
+            let mut decl = rename(key, &field);
+            if optional {
+                decl.push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+
+                // Nullable optional properties are represented as a single
+                // Option, rather than distinguishing null from absent.
+                if !sub_schema.nullable() {
+                    expr = format!("Option<{}>", expr);
+                }
+            }
+
+            decl.push_str(&format!("    pub {}: {},\n", field, expr));
+            decls.push(decl);
+        }
+
+        self.decls[index] = if decls.is_empty() {
+            format!("{}pub struct {} {{}}\n", DERIVES, name)
+        } else {
+            format!(
+                "{}pub struct {} {{\n{}}}\n",
+                DERIVES,
+                name,
+                decls.join("\n")
+            )
+        };
+    }
+
+    /// Reserves a spot for a declaration, so that declarations come before the
+    /// ones they depend on.
+    fn reserve_decl(&mut self) -> usize {
+        self.decls.push(String::new());
+        self.decls.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+    use serde_json::{json, Value};
+
+    fn convert_jtd(schema: Value) -> String {
+        to_rust(&jtd_schema(schema))
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(
+            "use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub type Root = HashMap<String, Option<Vec<DateTime<FixedOffset>>>>;
+",
+            convert_jtd(json!({
+                "values": { "elements": { "type": "timestamp" }, "nullable": true },
+            }))
+        );
+    }
+
+    #[test]
+    fn type_override() {
+        assert_eq!(
+            "use serde::{Deserialize, Serialize};
+
+pub type Root = Option<i64>;
+",
+            convert_jtd(json!({
+                "type": "float64",
+                "metadata": { "rustType": "i64" },
+                "nullable": true,
+            }))
+        );
+    }
+
+    #[test]
+    fn enums() {
+        assert_eq!(
+            "use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    pub status: Option<RootStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RootStatus {
+    #[serde(rename = \"IN_PROGRESS\")]
+    InProgress,
+    #[serde(rename = \"done\")]
+    Done,
+}
+",
+            convert_jtd(json!({
+                "properties": {
+                    "status": { "enum": ["IN_PROGRESS", "done"], "nullable": true },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            "use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = \"type\")]
+pub enum Root {
+    #[serde(rename = \"bar\")]
+    Bar(RootBar),
+    #[serde(rename = \"foo\")]
+    Foo(RootFoo),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootBar {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootFoo {
+    #[serde(rename = \"2x\")]
+    pub _2x: u8,
+
+    #[serde(rename = \"fn\")]
+    pub fn_: serde_json::Value,
+}
+",
+            convert_jtd(json!({
+                "discriminator": "type",
+                "mapping": {
+                    "foo": { "properties": { "2x": { "type": "uint8" }, "fn": {} }},
+                    "bar": { "properties": {}},
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn self_keyword() {
+        assert_eq!(
+            "use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Self_ {
+    #[serde(rename = \"self\")]
+    Self_,
+}
+",
+            convert_jtd(json!({ "definitions": { "self": { "enum": ["self"] }}}))
+        );
+    }
+
+    #[test]
+    fn reserved_keywords() {
+        assert_eq!(
+            "use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    #[serde(rename = \"abstract\")]
+    pub abstract_: String,
+
+    #[serde(rename = \"final\")]
+    pub final_: u8,
+}
+",
+            convert_jtd(json!({
+                "properties": {
+                    "final": { "type": "uint8" },
+                    "abstract": { "type": "string" },
+                },
+            }))
+        );
+    }
+}
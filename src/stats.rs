@@ -0,0 +1,205 @@
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+/// How many characters of a string to keep in a sample.
+const MAX_SAMPLE_LEN: usize = 100;
+
+/// Statistics about the examples seen at one part of the input.
+#[derive(Debug, Default)]
+pub struct Stats {
+    count: usize,
+    nulls: usize,
+    samples: Vec<Value>,
+    min: Option<Number>,
+    max: Option<Number>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl Stats {
+    /// Records an example. Up to `max_samples` distinct strings, numbers and
+    /// booleans are kept as samples.
+    pub fn record(mut self, value: &Value, max_samples: usize) -> Self {
+        self.count += 1;
+
+        match value {
+            Value::Null => self.nulls += 1,
+            Value::Number(n) => {
+                match &self.min {
+                    Some(min) if compare(n, min) != Ordering::Less => {}
+                    _ => self.min = Some(n.clone()),
+                }
+
+                match &self.max {
+                    Some(max) if compare(n, max) != Ordering::Greater => {}
+                    _ => self.max = Some(n.clone()),
+                }
+            }
+            Value::String(s) => {
+                let len = s.chars().count();
+                self.min_length = Some(self.min_length.map_or(len, |min| min.min(len)));
+                self.max_length = Some(self.max_length.map_or(len, |max| max.max(len)));
+            }
+            _ => {}
+        }
+
+        if self.samples.len() < max_samples {
+            let sample = match value {
+                Value::String(s) if s.chars().count() > MAX_SAMPLE_LEN => Some(Value::String(
+                    s.chars()
+                        .take(MAX_SAMPLE_LEN)
+                        .chain("...".chars())
+                        .collect(),
+                )),
+                Value::String(_) | Value::Number(_) | Value::Bool(_) => Some(value.clone()),
+                _ => None,
+            };
+
+            if let Some(sample) = sample {
+                if !self.samples.contains(&sample) {
+                    self.samples.push(sample);
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Leaves out everything that could reveal the examples themselves, keeping
+    /// only how many there were and how many were null.
+    pub fn redacted(self) -> Self {
+        Self {
+            count: self.count,
+            nulls: self.nulls,
+            ..Self::default()
+        }
+    }
+
+    pub fn into_value(self) -> Value {
+        let mut out = Map::new();
+        out.insert("count".to_owned(), self.count.into());
+
+        if self.nulls > 0 {
+            out.insert(
+                "nullRatio".to_owned(),
+                (self.nulls as f64 / self.count as f64).into(),
+            );
+        }
+
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            out.insert("min".to_owned(), Value::Number(min));
+            out.insert("max".to_owned(), Value::Number(max));
+        }
+
+        if let (Some(min), Some(max)) = (self.min_length, self.max_length) {
+            out.insert("minLength".to_owned(), min.into());
+            out.insert("maxLength".to_owned(), max.into());
+        }
+
+        if !self.samples.is_empty() {
+            out.insert("samples".to_owned(), self.samples.into());
+        }
+
+        Value::Object(out)
+    }
+}
+
+/// Compares two numbers. Integers are compared exactly, even if they're too
+/// large for an f64 to tell them apart.
+fn compare(a: &Number, b: &Number) -> Ordering {
+    match (as_i128(a), as_i128(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => {
+            let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+fn as_i128(n: &Number) -> Option<i128> {
+    n.as_i64()
+        .map(i128::from)
+        .or_else(|| n.as_u64().map(i128::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn stats() {
+        let stats = [json!(3), json!(null), json!(-1.5), json!(3), json!(10)]
+            .iter()
+            .fold(Stats::default(), |stats, value| stats.record(value, 3));
+
+        assert_eq!(
+            json!({
+                "count": 5,
+                "nullRatio": 0.2,
+                "min": -1.5,
+                "max": 10,
+                "samples": [3, -1.5, 10],
+            }),
+            stats.into_value()
+        );
+
+        let stats = [json!("a"), json!("abc"), json!("x".repeat(200))]
+            .iter()
+            .fold(Stats::default(), |stats, value| stats.record(value, 0));
+
+        assert_eq!(
+            json!({ "count": 3, "minLength": 1, "maxLength": 200 }),
+            stats.into_value()
+        );
+    }
+
+    #[test]
+    fn large_integers() {
+        let stats = [
+            json!(9007199254740993u64),
+            json!(9007199254740992u64),
+            json!(u64::MAX),
+            json!(u64::MAX - 1),
+            json!(i64::MIN + 1),
+            json!(i64::MIN),
+        ]
+        .iter()
+        .fold(Stats::default(), |stats, value| stats.record(value, 0));
+
+        assert_eq!(
+            json!({ "count": 6, "min": i64::MIN, "max": u64::MAX }),
+            stats.into_value()
+        );
+
+        let stats = [json!(9007199254740993u64), json!(9007199254740992u64)]
+            .iter()
+            .fold(Stats::default(), |stats, value| stats.record(value, 0));
+
+        assert_eq!(
+            json!({ "count": 2, "min": 9007199254740992u64, "max": 9007199254740993u64 }),
+            stats.into_value()
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        let stats = [json!(3), json!(null), json!("abc")]
+            .iter()
+            .fold(Stats::default(), |stats, value| stats.record(value, 3));
+
+        assert_eq!(
+            json!({ "count": 3, "nullRatio": 1.0 / 3.0 }),
+            stats.redacted().into_value()
+        );
+    }
+
+    #[test]
+    fn long_samples() {
+        let stats = Stats::default().record(&json!("x".repeat(200)), 1);
+        assert_eq!(
+            json!([format!("{}...", "x".repeat(100))]),
+            stats.into_value()["samples"]
+        );
+    }
+}
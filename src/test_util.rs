@@ -0,0 +1,7 @@
+use jtd::{Schema, SerdeSchema};
+use serde_json::Value;
+
+/// Parses a JSON Typedef schema written as JSON, for testing the emitters.
+pub fn jtd_schema(schema: Value) -> Schema {
+    Schema::from_serde_schema(serde_json::from_value::<SerdeSchema>(schema).unwrap()).unwrap()
+}
@@ -0,0 +1,435 @@
+use crate::names::{pascal_case, type_name, Names};
+use jtd::{Schema, Type};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Converts a JSON Typedef schema into TypeScript type declarations.
+///
+/// The root schema is declared as `Root`, and each of the `definitions` is
+/// declared using a PascalCase version of its name. If the root schema is
+/// empty but has `definitions`, then only the definitions are declared.
+///
+/// Objects are declared as interfaces named after the path to them. Optional
+/// properties use `?:`, nullable schemas are a union with `null`, the values
+/// form becomes a `Record<string, T>`, the enum form a union of string
+/// literals, and the discriminator form a union of interfaces that each have a
+/// literal type for the discriminator property. A `typescriptType` in the
+/// `metadata` of a type form overrides the TypeScript type used for it.
+///
+/// ```
+/// use serde_json::json;
+/// # use jtd::{Schema, SerdeSchema};
+/// use jtd_infer::to_typescript;
+///
+/// let schema = json!({
+///     "properties": { "tags": { "elements": { "type": "string" }}},
+///     "optionalProperties": { "age": { "type": "uint8", "nullable": true }},
+/// });
+/// # let schema = serde_json::from_value::<SerdeSchema>(schema).unwrap();
+/// # let schema = Schema::from_serde_schema(schema).unwrap();
+///
+/// assert_eq!(
+///     "export interface Root {
+///   age?: number | null;
+///   tags: string[];
+/// }
+/// ",
+///     to_typescript(&schema),
+/// );
+/// ```
+pub fn to_typescript(schema: &Schema) -> String {
+    let mut emitter = Emitter::default();
+
+    for name in schema.definitions().keys() {
+        let type_name = emitter.names.unique(&type_name(name));
+        emitter.refs.insert(name.clone(), type_name);
+    }
+
+    for (name, sub_schema) in schema.definitions() {
+        let type_name = emitter.refs[name].clone();
+        emitter.declare(&type_name, sub_schema);
+    }
+
+    let is_definitions_only =
+        matches!(schema, Schema::Empty { .. }) && !schema.definitions().is_empty();
+
+    if !is_definitions_only {
+        let root = emitter.names.unique("Root");
+        emitter.declare(&root, schema);
+    }
+
+    emitter.decls.join("\n")
+}
+
+#[derive(Default)]
+struct Emitter {
+    names: Names,
+    refs: BTreeMap<String, String>,
+    decls: Vec<String>,
+}
+
+impl Emitter {
+    /// Declares `schema` under a name that has already been reserved.
+    fn declare(&mut self, name: &str, schema: &Schema) {
+        match schema {
+            Schema::Properties {
+                properties,
+                optional_properties,
+                nullable: true,
+                ..
+            } => {
+                // An interface can't be nullable, so this is a type alias for
+                // an object type instead, which keeps the name for the object.
+                let index = self.reserve_decl();
+                let object = self.object_type(name, None, properties, optional_properties);
+                self.decls[index] = format!("export type {} = {} | null;\n", name, object);
+            }
+            Schema::Properties { .. } | Schema::Discriminator { .. } => {
+                self.declare_object(name, schema);
+            }
+            _ => {
+                let index = self.reserve_decl();
+                let expr = self.expr(name, schema);
+                self.decls[index] = format!("export type {} = {};\n", name, expr);
+            }
+        }
+    }
+
+    /// Returns a TypeScript type expression for `schema`, declaring any
+    /// interfaces it needs with names derived from `name`.
+    fn expr(&mut self, name: &str, schema: &Schema) -> String {
+        let expr = match schema {
+            Schema::Empty { .. } => return "any".to_owned(),
+            Schema::Ref { ref_, .. } => self.refs[ref_].clone(),
+            // Code generators let metadata override the type to use for a
+            // primitive.
+            Schema::Type { metadata, .. }
+                if metadata.get("typescriptType").is_some_and(Value::is_string) =>
+            {
+                metadata["typescriptType"].as_str().unwrap().to_owned()
+            }
+            Schema::Type { type_, .. } => match type_ {
+                Type::Boolean => "boolean",
+                Type::String | Type::Timestamp => "string",
+                _ => "number",
+            }
+            .to_owned(),
+            Schema::Enum { enum_, .. } => enum_
+                .iter()
+                .map(|value| serde_json::to_string(value).unwrap())
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Schema::Elements { elements, .. } => {
+                let elements = self.expr(&format!("{}Element", name), elements);
+                if elements.contains(" | ") {
+                    format!("({})[]", elements)
+                } else {
+                    format!("{}[]", elements)
+                }
+            }
+            Schema::Values { values, .. } => {
+                format!(
+                    "Record<string, {}>",
+                    self.expr(&format!("{}Value", name), values)
+                )
+            }
+            Schema::Properties { .. } => {
+                let name = self.names.unique(name);
+                self.declare_object(&name, schema);
+                name
+            }
+            // The type alias for a discriminator already includes null if the
+            // discriminator is nullable.
+            Schema::Discriminator { .. } => {
+                let name = self.names.unique(name);
+                self.declare_object(&name, schema);
+                return name;
+            }
+        };
+
+        if schema.nullable() {
+            format!("{} | null", expr)
+        } else {
+            expr
+        }
+    }
+
+    fn declare_object(&mut self, name: &str, schema: &Schema) {
+        match schema {
+            Schema::Properties {
+                properties,
+                optional_properties,
+                ..
+            } => self.declare_interface(name, None, properties, optional_properties),
+            Schema::Discriminator {
+                discriminator,
+                mapping,
+                ..
+            } => {
+                let index = self.reserve_decl();
+
+                let mut variants = Vec::new();
+                for (tag, sub_schema) in mapping {
+                    let variant = self.names.unique(&format!("{}{}", name, pascal_case(tag)));
+
+                    if let Schema::Properties {
+                        properties,
+                        optional_properties,
+                        ..
+                    } = sub_schema
+                    {
+                        self.declare_interface(
+                            &variant,
+                            Some((discriminator, tag)),
+                            properties,
+                            optional_properties,
+                        );
+                    }
+
+                    variants.push(variant);
+                }
+
+                if schema.nullable() {
+                    variants.push("null".to_owned());
+                }
+
+                self.decls[index] = format!("export type {} = {};\n", name, variants.join(" | "));
+            }
+            _ => unreachable!("declared non-object as interface"),
+        }
+    }
+
+    fn declare_interface(
+        &mut self,
+        name: &str,
+        tag: Option<(&str, &str)>,
+        properties: &BTreeMap<String, Schema>,
+        optional_properties: &BTreeMap<String, Schema>,
+    ) {
+        let index = self.reserve_decl();
+        let object = self.object_type(name, tag, properties, optional_properties);
+        self.decls[index] = format!("export interface {} {}\n", name, object);
+    }
+
+    /// Returns a TypeScript object type with the given properties, declaring
+    /// any interfaces they need with names derived from `name`.
+    fn object_type(
+        &mut self,
+        name: &str,
+        tag: Option<(&str, &str)>,
+        properties: &BTreeMap<String, Schema>,
+        optional_properties: &BTreeMap<String, Schema>,
+    ) -> String {
+        let mut decl = "{\n".to_owned();
+        if let Some((discriminator, tag)) = tag {
+            decl.push_str(&format!(
+                "  {}: {};\n",
+                property_name(discriminator),
+                serde_json::to_string(tag).unwrap()
+            ));
+        }
+
+        let fields = properties
+            .iter()
+            .map(|field| (field, false))
+            .chain(optional_properties.iter().map(|field| (field, true)));
+
+        let mut fields: Vec<_> = fields.collect();
+        fields.sort_by_key(|((key, _), _)| key.as_str());
+
+        for ((key, sub_schema), optional) in fields {
+            let expr = self.expr(&format!("{}{}", name, pascal_case(key)), sub_schema);
+            decl.push_str(&format!(
+                "  {}{}: {};\n",
+                property_name(key),
+                if optional { "?" } else { "" },
+                expr
+            ));
+        }
+
+        decl.push('}');
+        decl
+    }
+
+    /// Reserves a spot for a declaration, so that declarations come before the
+    /// ones they depend on.
+    fn reserve_decl(&mut self) -> usize {
+        self.decls.push(String::new());
+        self.decls.len() - 1
+    }
+}
+
+fn property_name(key: &str) -> String {
+    let is_identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        key.to_owned()
+    } else {
+        serde_json::to_string(key).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::jtd_schema;
+    use serde_json::{json, Value};
+
+    fn convert_jtd(schema: Value) -> String {
+        to_typescript(&jtd_schema(schema))
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!("export type Root = any;\n", convert_jtd(json!({})));
+        assert_eq!(
+            "export type Root = string | null;\n",
+            convert_jtd(json!({ "type": "timestamp", "nullable": true }))
+        );
+        assert_eq!(
+            "export type Root = \"a\" | \"b\";\n",
+            convert_jtd(json!({ "enum": ["b", "a"] }))
+        );
+        assert_eq!(
+            "export type Root = (number | null)[];\n",
+            convert_jtd(json!({ "elements": { "type": "float64", "nullable": true }}))
+        );
+        assert_eq!(
+            "export type Root = Record<string, boolean>;\n",
+            convert_jtd(json!({ "values": { "type": "boolean" }}))
+        );
+    }
+
+    #[test]
+    fn type_override() {
+        assert_eq!(
+            "export type Root = bigint;\n",
+            convert_jtd(json!({ "type": "float64", "metadata": { "typescriptType": "bigint" }}))
+        );
+    }
+
+    #[test]
+    fn nested_objects() {
+        assert_eq!(
+            "export interface Root {
+  \"first-name\": string;
+  pets?: RootPetsElement[];
+}
+
+export interface RootPetsElement {
+  name: string;
+}
+",
+            convert_jtd(json!({
+                "properties": { "first-name": { "type": "string" }},
+                "optionalProperties": {
+                    "pets": { "elements": { "properties": { "name": { "type": "string" }}}},
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn discriminator() {
+        assert_eq!(
+            "export type Root = RootBar | RootFoo;
+
+export interface RootBar {
+  kind: \"bar\";
+}
+
+export interface RootFoo {
+  kind: \"foo\";
+  x: number;
+}
+",
+            convert_jtd(json!({
+                "discriminator": "kind",
+                "mapping": {
+                    "foo": { "properties": { "x": { "type": "uint8" }}},
+                    "bar": { "properties": {}},
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn definitions() {
+        assert_eq!(
+            "export interface GetUsersResponse {
+  user: User;
+}
+
+export type User = string;
+",
+            convert_jtd(json!({
+                "definitions": {
+                    "get_users_response": { "properties": { "user": { "ref": "user" }}},
+                    "user": { "type": "string" },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn nullable_discriminator() {
+        assert_eq!(
+            "export interface Root {
+  pet: RootPet;
+}
+
+export type RootPet = RootPetCat | null;
+
+export interface RootPetCat {
+  kind: \"cat\";
+}
+",
+            convert_jtd(json!({
+                "properties": {
+                    "pet": {
+                        "discriminator": "kind",
+                        "mapping": { "cat": { "properties": {}}},
+                        "nullable": true,
+                    },
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn nullable_root() {
+        assert_eq!(
+            "export type Root = {
+  tags: RootTagsElement[];
+} | null;
+
+export interface RootTagsElement {
+  name: string;
+}
+",
+            convert_jtd(json!({
+                "properties": {
+                    "tags": { "elements": { "properties": { "name": { "type": "string" }}}},
+                },
+                "nullable": true,
+            }))
+        );
+
+        assert_eq!(
+            "export type Root = RootFoo | null;
+
+export interface RootFoo {
+  kind: \"foo\";
+}
+",
+            convert_jtd(json!({
+                "discriminator": "kind",
+                "mapping": { "foo": { "properties": {}}},
+                "nullable": true,
+            }))
+        );
+    }
+}